@@ -0,0 +1,408 @@
+use indicatif::ProgressBar;
+use oxrdf::vocab::{rdf, xsd};
+use oxrdf::{Graph, Literal, NamedNode, NamedOrBlankNode, TermRef, Triple};
+use oxttl::NTriplesParser;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::client::USER_AGENT;
+use crate::failures::Failure;
+use crate::Error;
+use crate::POKE;
+use crate::SCHEMA;
+
+// Sprite URLs from PokeAPI point into the PokeAPI/sprites repository, a clone of it can be used
+// as a local source as is
+static SPRITES_REPOSITORY: &str = "https://raw.githubusercontent.com/PokeAPI/sprites/master/";
+
+// Mirrored sprites, one tab separated line per URL, appended as files are stored
+static MANIFEST: &str = "manifest.tsv";
+
+/// Where sprite files are read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssetSource {
+    /// Download every sprite from its URL.
+    Remote,
+    /// Read sprites from a local copy of the sprites repository, without network access.
+    Local(PathBuf),
+}
+
+// A sprite stored in the content-addressed directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StoredAsset {
+    sha256: String,
+    size: u64,
+    dimensions: Option<(u32, u32)>,
+    file: String,
+}
+
+/// Reads an N-Triples dump, mirrors every sprite it references into `store` and writes the dump
+/// with local sprite URLs as N-Triples. Sprites already listed in the store's manifest are not
+/// fetched again, so an interrupted run can simply be restarted. Returns the sprites that could not
+/// be mirrored.
+pub async fn mirror_assets_file(
+    input: &str,
+    output: &str,
+    store: &Path,
+    source: &AssetSource,
+    base_url: &str,
+) -> Result<Vec<Failure>, Error> {
+    let input_file = std::fs::File::open(input)
+        .map_err(|e| Error::io(format!("Failed to open input file {}", input), e))?;
+    let mut graph = Graph::new();
+    for triple in NTriplesParser::new().for_reader(BufReader::new(input_file)) {
+        graph.insert(&triple?);
+    }
+
+    let (mirrored, failures) = mirror_assets(&graph, store, source, base_url).await?;
+
+    let output_file = std::fs::File::create(output)
+        .map_err(|e| Error::io(format!("Failed to create output file {}", output), e))?;
+    let mut writer = BufWriter::new(output_file);
+    for t in mirrored.iter() {
+        writeln!(writer, "{t} .")?;
+    }
+    writer.flush()?;
+    Ok(failures)
+}
+
+/// Stores every `schema:ImageObject` of the graph under `store`, named by the sha256 of its
+/// content, and points the image at `base_url` instead. The original URL is kept as
+/// `poke:sourceUrl` and checksum, byte size and pixel dimensions are added. Sprites that cannot
+/// be fetched keep their original URL and are returned as failures of the `sprite` collection.
+pub async fn mirror_assets(
+    graph: &Graph,
+    store: &Path,
+    source: &AssetSource,
+    base_url: &str,
+) -> Result<(Graph, Vec<Failure>), Error> {
+    let schema = |local: &str| NamedNode::new(format!("{SCHEMA}{local}"));
+    let poke = |local: &str| NamedNode::new(format!("{POKE}{local}"));
+    let image_object = schema("ImageObject")?;
+    let content_url = schema("contentUrl")?;
+
+    let mut images: Vec<(NamedOrBlankNode, NamedNode)> = vec![];
+    for image in graph.subjects_for_predicate_object(rdf::TYPE, &image_object) {
+        if let Some(TermRef::NamedNode(url)) =
+            graph.object_for_subject_predicate(image, &content_url)
+        {
+            images.push((image.into_owned(), url.into_owned()));
+        }
+    }
+    let urls: BTreeSet<&str> = images.iter().map(|(_, url)| url.as_str()).collect();
+
+    std::fs::create_dir_all(store).map_err(|e| {
+        Error::io(
+            format!("Failed to create asset directory {}", store.display()),
+            e,
+        )
+    })?;
+    let mut stored = read_manifest(store)?;
+    let mut manifest = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(store.join(MANIFEST))?;
+    let client = reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .build()
+        .map_err(Error::Client)?;
+
+    let pb = ProgressBar::new(urls.len().try_into().unwrap()).with_style(crate::create_bar_style());
+    let mut failures = vec![];
+    for (index, url) in urls.iter().enumerate() {
+        pb.set_message(format!("sprite {}/{}", index + 1, urls.len()));
+        pb.inc(1);
+        // resume: skip what is already in the store
+        if stored
+            .get(*url)
+            .is_some_and(|asset| store.join(&asset.file).exists())
+        {
+            continue;
+        }
+        let bytes = match fetch(&client, source, url).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                failures.push(Failure {
+                    collection: "sprite".to_string(),
+                    url: url.to_string(),
+                    error: e.to_string(),
+                    retryable: e.is_retryable(),
+                });
+                continue;
+            }
+        };
+        let asset = store_asset(store, url, &bytes)?;
+        writeln!(
+            manifest,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            url,
+            asset.sha256,
+            asset.size,
+            asset
+                .dimensions
+                .map(|d| d.0.to_string())
+                .unwrap_or_default(),
+            asset
+                .dimensions
+                .map(|d| d.1.to_string())
+                .unwrap_or_default(),
+            asset.file
+        )?;
+        manifest.flush()?;
+        stored.insert(url.to_string(), asset);
+    }
+    pb.finish_with_message("done");
+
+    let mut mirrored = graph.clone();
+    for (image, url) in &images {
+        let Some(asset) = stored.get(url.as_str()) else {
+            continue;
+        };
+        mirrored.remove(&Triple::new(
+            image.clone(),
+            content_url.clone(),
+            url.clone(),
+        ));
+        mirrored.insert(&Triple::new(
+            image.clone(),
+            content_url.clone(),
+            NamedNode::new(format!("{}{}", base_url, asset.file))?,
+        ));
+        mirrored.insert(&Triple::new(image.clone(), poke("sourceUrl")?, url.clone()));
+        mirrored.insert(&Triple::new(
+            image.clone(),
+            poke("sha256")?,
+            Literal::new_simple_literal(&asset.sha256),
+        ));
+        mirrored.insert(&Triple::new(
+            image.clone(),
+            schema("contentSize")?,
+            Literal::new_typed_literal(asset.size.to_string(), xsd::INTEGER),
+        ));
+        if let Some((width, height)) = asset.dimensions {
+            mirrored.insert(&Triple::new(
+                image.clone(),
+                schema("width")?,
+                Literal::new_typed_literal(width.to_string(), xsd::INTEGER),
+            ));
+            mirrored.insert(&Triple::new(
+                image.clone(),
+                schema("height")?,
+                Literal::new_typed_literal(height.to_string(), xsd::INTEGER),
+            ));
+        }
+    }
+    Ok((mirrored, failures))
+}
+
+async fn fetch(
+    client: &reqwest::Client,
+    source: &AssetSource,
+    url: &str,
+) -> Result<Vec<u8>, Error> {
+    match source {
+        AssetSource::Remote => {
+            let download = |source| Error::Download {
+                url: url.to_string(),
+                source,
+            };
+            let response = client.get(url).send().await.map_err(download)?;
+            let bytes = response
+                .error_for_status()
+                .map_err(download)?
+                .bytes()
+                .await
+                .map_err(download)?;
+            Ok(bytes.to_vec())
+        }
+        AssetSource::Local(dir) => {
+            let path = dir.join(local_path(url));
+            Ok(std::fs::read(&path)
+                .map_err(|e| Error::io(format!("Failed to read {}", path.display()), e))?)
+        }
+    }
+}
+
+// Path of a sprite inside a local copy of the sprites repository, other hosts get their own
+// directory
+fn local_path(url: &str) -> String {
+    match url.strip_prefix(SPRITES_REPOSITORY) {
+        Some(path) => path.to_string(),
+        None => url
+            .split_once("://")
+            .map_or(url, |(_, path)| path)
+            .to_string(),
+    }
+}
+
+// Writes the sprite as `{sha256[..2]}/{sha256}.{extension}`. Identical files share one copy.
+fn store_asset(store: &Path, url: &str, bytes: &[u8]) -> Result<StoredAsset, Error> {
+    let sha256 = format!("{:x}", Sha256::digest(bytes));
+    let extension = match url.rsplit_once('.') {
+        Some((_, extension)) if extension.len() <= 4 && !extension.contains('/') => extension,
+        _ => "bin",
+    };
+    let file = format!("{}/{}.{}", &sha256[..2], sha256, extension);
+    let path = store.join(&file);
+    if !path.exists() {
+        std::fs::create_dir_all(store.join(&sha256[..2]))?;
+        // written under a temporary name first, so an interrupted run leaves no partial sprite
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, bytes)?;
+        std::fs::rename(&partial, &path)?;
+    }
+    Ok(StoredAsset {
+        sha256,
+        size: bytes.len() as u64,
+        dimensions: dimensions(bytes),
+        file,
+    })
+}
+
+fn read_manifest(store: &Path) -> Result<HashMap<String, StoredAsset>, Error> {
+    let mut stored = HashMap::new();
+    let path = store.join(MANIFEST);
+    if !path.exists() {
+        return Ok(stored);
+    }
+    for line in BufReader::new(std::fs::File::open(&path)?).lines() {
+        let line = line?;
+        let fields: Vec<&str> = line.split('\t').collect();
+        // a line cut short by an interrupted run is fetched again
+        let [url, sha256, size, width, height, file] = fields[..] else {
+            continue;
+        };
+        let Ok(size) = size.parse() else {
+            continue;
+        };
+        let dimensions = match (width.parse(), height.parse()) {
+            (Ok(width), Ok(height)) => Some((width, height)),
+            _ => None,
+        };
+        stored.insert(
+            url.to_string(),
+            StoredAsset {
+                sha256: sha256.to_string(),
+                size,
+                dimensions,
+                file: file.to_string(),
+            },
+        );
+    }
+    Ok(stored)
+}
+
+// Pixel width and height from the PNG or GIF header, or from the root element of an SVG
+fn dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") && bytes.len() >= 24 {
+        let width = u32::from_be_bytes(bytes[16..20].try_into().ok()?);
+        let height = u32::from_be_bytes(bytes[20..24].try_into().ok()?);
+        return Some((width, height));
+    }
+    if (bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a")) && bytes.len() >= 10 {
+        let width = u16::from_le_bytes(bytes[6..8].try_into().ok()?);
+        let height = u16::from_le_bytes(bytes[8..10].try_into().ok()?);
+        return Some((width.into(), height.into()));
+    }
+    let text = std::str::from_utf8(bytes).ok()?;
+    let svg = &text[text.find("<svg")?..];
+    let svg = &svg[..svg.find('>')?];
+    let attribute = |name: &str| -> Option<u32> {
+        let value = svg.split(&format!(" {name}=\"")).nth(1)?;
+        let number: String = value
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == '.')
+            .collect();
+        Some(number.parse::<f64>().ok()?.round() as u32)
+    };
+    Some((attribute("width")?, attribute("height")?))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mirror_assets_offline() {
+        let dir = std::env::temp_dir().join(format!("pokemon2rdf-assets-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let sprites = dir.join("sprites");
+        let store = dir.join("store");
+        std::fs::create_dir_all(sprites.join("sprites/items")).unwrap();
+        // PNG signature and IHDR chunk of a 30x30 image
+        let mut png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        png.extend_from_slice(&30u32.to_be_bytes());
+        png.extend_from_slice(&30u32.to_be_bytes());
+        std::fs::write(sprites.join("sprites/items/master-ball.png"), &png).unwrap();
+
+        let mut graph = Graph::new();
+        for triple in NTriplesParser::new().for_slice(
+            r#"
+<https://pokeapi.co/api/v2/item/1/> <https://schema.org/image> _:item1_sprite0 .
+_:item1_sprite0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://schema.org/ImageObject> .
+_:item1_sprite0 <https://schema.org/contentUrl> <https://raw.githubusercontent.com/PokeAPI/sprites/master/sprites/items/master-ball.png> .
+_:item2_sprite0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://schema.org/ImageObject> .
+_:item2_sprite0 <https://schema.org/contentUrl> <https://raw.githubusercontent.com/PokeAPI/sprites/master/sprites/items/missing.png> .
+"#,
+        ) {
+            graph.insert(&triple.unwrap());
+        }
+
+        let source = AssetSource::Local(sprites.clone());
+        let base_url = "https://cdn.example.org/sprites/";
+        let (mirrored, failures) = mirror_assets(&graph, &store, &source, base_url)
+            .await
+            .unwrap();
+        let failed: Vec<&str> = failures.iter().map(|f| f.url.as_str()).collect();
+        assert_eq!(
+            failed,
+            ["https://raw.githubusercontent.com/PokeAPI/sprites/master/sprites/items/missing.png"]
+        );
+
+        let sha256 = format!("{:x}", Sha256::digest(&png));
+        let file = format!("{}/{}.png", &sha256[..2], sha256);
+        assert_eq!(std::fs::read(store.join(&file)).unwrap(), png);
+        let mut expected = Graph::new();
+        for triple in NTriplesParser::new().for_slice(&format!(
+            r#"
+<https://pokeapi.co/api/v2/item/1/> <https://schema.org/image> _:item1_sprite0 .
+_:item1_sprite0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://schema.org/ImageObject> .
+_:item1_sprite0 <https://schema.org/contentUrl> <{base_url}{file}> .
+_:item1_sprite0 <http://purl.org/pokemon/ontology#sourceUrl> <https://raw.githubusercontent.com/PokeAPI/sprites/master/sprites/items/master-ball.png> .
+_:item1_sprite0 <http://purl.org/pokemon/ontology#sha256> "{sha256}" .
+_:item1_sprite0 <https://schema.org/contentSize> "24"^^<http://www.w3.org/2001/XMLSchema#integer> .
+_:item1_sprite0 <https://schema.org/width> "30"^^<http://www.w3.org/2001/XMLSchema#integer> .
+_:item1_sprite0 <https://schema.org/height> "30"^^<http://www.w3.org/2001/XMLSchema#integer> .
+_:item2_sprite0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://schema.org/ImageObject> .
+_:item2_sprite0 <https://schema.org/contentUrl> <https://raw.githubusercontent.com/PokeAPI/sprites/master/sprites/items/missing.png> .
+"#
+        )) {
+            expected.insert(&triple.unwrap());
+        }
+        assert_eq!(mirrored, expected);
+
+        // a second run takes everything from the manifest, even without the source
+        std::fs::remove_dir_all(&sprites).unwrap();
+        let (resumed, failures) = mirror_assets(&graph, &store, &source, base_url)
+            .await
+            .unwrap();
+        assert_eq!(resumed, expected);
+        assert_eq!(failures.len(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_dimensions() {
+        assert_eq!(dimensions(b"GIF89a\x28\x00\x1e\x00"), Some((40, 30)));
+        assert_eq!(
+            dimensions(br#"<?xml version="1.0"?><svg xmlns="http://www.w3.org/2000/svg" width="96.5px" height="96">"#),
+            Some((97, 96))
+        );
+        assert_eq!(dimensions(b"not an image"), None);
+    }
+}
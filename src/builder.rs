@@ -0,0 +1,265 @@
+use indicatif::{MultiProgress, ProgressDrawTarget};
+use oxrdf::{Graph, Quad, Term, Triple};
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+
+use crate::client::{Client, ClientConfig};
+use crate::collections::Collection;
+use crate::failures::{Failure, FailureMode, Failures};
+use crate::sink::TripleSink;
+use crate::Error;
+
+/// Converts PokeAPI collections to RDF in memory. Nothing is written to disk or printed, progress
+/// bars are hidden unless asked for. [`build_graph_with`](crate::build_graph_with) writes the
+/// dump on top of it.
+///
+/// ```no_run
+/// # async fn example() -> Result<(), pokemon2rdf::Error> {
+/// use pokemon2rdf::{Builder, Collection, FailureMode};
+///
+/// let (graph, failures) = Builder::new()
+///     .collections([Collection::Type, Collection::Pokemon])
+///     .failure_mode(FailureMode::Skip)
+///     .graph()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Builder {
+    client: ClientConfig,
+    mode: FailureMode,
+    collections: Vec<Collection>,
+    progress: bool,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Builder {
+            client: ClientConfig::default(),
+            mode: FailureMode::default(),
+            collections: Collection::ALL.to_vec(),
+            progress: false,
+        }
+    }
+}
+
+impl Builder {
+    /// All collections from PokeAPI with the default client, aborting at the first failure.
+    pub fn new() -> Self {
+        Builder::default()
+    }
+
+    /// Request rate, retries, User-Agent and base URL used for PokeAPI.
+    pub fn client(mut self, config: ClientConfig) -> Self {
+        self.client = config;
+        self
+    }
+
+    /// With [`FailureMode::Skip`] resources that cannot be fetched are left out and returned
+    /// instead of aborting.
+    pub fn failure_mode(mut self, mode: FailureMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Only convert these collections, in the given order.
+    pub fn collections(mut self, collections: impl IntoIterator<Item = Collection>) -> Self {
+        self.collections = collections.into_iter().collect();
+        self
+    }
+
+    /// Show a progress bar per collection on stderr.
+    pub fn progress(mut self, progress: bool) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Converts into `sink` and returns the skipped resources. Pass `&mut sink` to keep using
+    /// the sink afterwards.
+    pub async fn stream(self, mut sink: impl TripleSink) -> Result<Vec<Failure>, Error> {
+        let client = Arc::new(Client::new(self.client.clone())?);
+        let failures = self.run(client, &mut sink).await?;
+        sink.finish()?;
+        Ok(failures)
+    }
+
+    /// Converts everything into one graph, returned with the skipped resources.
+    pub async fn graph(self) -> Result<(Graph, Vec<Failure>), Error> {
+        let mut graph = Graph::new();
+        let failures = self.stream(&mut graph).await?;
+        Ok((graph, failures))
+    }
+
+    /// Converts the resource `id_or_name` of `collection` alone, e.g. `pikachu` or `25` of
+    /// [`Collection::Pokemon`], into `sink` and returns the skipped resources. With a `depth`
+    /// above 0 the resources it links to, such as species, types and abilities, are converted
+    /// too, up to `depth` links away; the collections set on the builder are ignored.
+    pub async fn resource(
+        self,
+        collection: Collection,
+        id_or_name: &str,
+        depth: usize,
+        mut sink: impl TripleSink,
+    ) -> Result<Vec<Failure>, Error> {
+        let client = Client::new(self.client)?;
+        let failures = Failures::new(self.mode);
+        let url = collection.resource_url(&client, id_or_name).await?;
+        let mut visited = HashSet::from([url.clone()]);
+        let mut queue = VecDeque::from([(collection, url, 0)]);
+        while let Some((collection, url, distance)) = queue.pop_front() {
+            let mut links = Links {
+                inner: &mut sink,
+                urls: vec![],
+            };
+            let converted = collection.convert_resource(&client, &url, &mut links).await;
+            if distance == 0 {
+                // the requested resource itself cannot be skipped
+                converted?;
+            } else {
+                failures.skip_failed(collection.endpoint(), converted)?;
+            }
+            if distance == depth {
+                continue;
+            }
+            for link in links.urls {
+                if let Some(linked) = Collection::of_url(&link) {
+                    if visited.insert(link.clone()) {
+                        queue.push_back((linked, link, distance + 1));
+                    }
+                }
+            }
+        }
+        sink.finish()?;
+        Ok(failures.into_inner())
+    }
+
+    // Runs the converters one collection after the other
+    pub(crate) async fn run(
+        self,
+        client: Arc<Client>,
+        sink: &mut dyn TripleSink,
+    ) -> Result<Vec<Failure>, Error> {
+        let bar = if self.progress {
+            MultiProgress::new()
+        } else {
+            MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+        };
+        let failures = Failures::new(self.mode);
+        for collection in self.collections {
+            collection
+                .convert(&bar, client.clone(), sink, &failures)
+                .await?;
+        }
+        Ok(failures.into_inner())
+    }
+}
+
+// Passes everything on and notes the converted PokeAPI resources the triples link to
+struct Links<'a> {
+    inner: &'a mut dyn TripleSink,
+    urls: Vec<String>,
+}
+
+impl Links<'_> {
+    fn note(&mut self, object: &Term) {
+        if let Term::NamedNode(node) = object {
+            if Collection::of_url(node.as_str()).is_some() {
+                self.urls.push(node.as_str().to_string());
+            }
+        }
+    }
+}
+
+impl TripleSink for Links<'_> {
+    fn start_resource(&mut self, url: &str) -> Result<(), Error> {
+        self.inner.start_resource(url)
+    }
+
+    fn triple(&mut self, triple: Triple) -> Result<(), Error> {
+        self.note(&triple.object);
+        self.inner.triple(triple)
+    }
+
+    fn quad(&mut self, quad: Quad) -> Result<(), Error> {
+        self.note(&quad.object);
+        self.inner.quad(quad)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::collections::fixtures;
+    use crate::mock::{Mock, MockServer};
+    use oxrdf::NamedNodeRef;
+
+    fn builder(server: &MockServer) -> Builder {
+        Builder::new().client(ClientConfig {
+            requests_per_second: 0.0,
+            base_url: server.base_url(),
+            ..ClientConfig::default()
+        })
+    }
+
+    // Only the selected collection ends up in the graph
+    #[tokio::test]
+    async fn test_graph() {
+        let server = Mock::new(fixtures::corpus()).start().await;
+        let (graph, failures) = builder(&server)
+            .collections([Collection::PokemonColor])
+            .graph()
+            .await
+            .unwrap();
+        assert_eq!(failures, vec![]);
+        let nt: String = graph.iter().map(|t| format!("{t} .\n")).collect();
+        fixtures::assert_golden_nt("colors", &nt);
+    }
+
+    // A resource by name or id converts like its collection
+    #[tokio::test]
+    async fn test_resource() {
+        let server = Mock::new(fixtures::corpus()).start().await;
+        for id in ["green", "5"] {
+            let mut graph = Graph::new();
+            let failures = builder(&server)
+                .resource(Collection::PokemonColor, id, 0, &mut graph)
+                .await
+                .unwrap();
+            assert_eq!(failures, vec![]);
+            fixtures::assert_golden("colors", &graph);
+        }
+        let unknown = builder(&server)
+            .resource(Collection::PokemonColor, "red", 0, Graph::new())
+            .await;
+        assert!(matches!(unknown, Err(Error::InvalidData(_))));
+    }
+
+    // Linked species are converted one link away, missing ones are skipped
+    #[tokio::test]
+    async fn test_resource_depth() {
+        let server = Mock::new(fixtures::corpus()).start().await;
+        let mut graph = Graph::new();
+        let failures = builder(&server)
+            .failure_mode(FailureMode::Skip)
+            .resource(Collection::PokemonColor, "green", 1, &mut graph)
+            .await
+            .unwrap();
+        let skipped: Vec<&str> = failures.iter().map(|f| f.url.as_str()).collect();
+        assert_eq!(
+            skipped,
+            [
+                "https://pokeapi.co/api/v2/pokemon-species/2/",
+                "https://pokeapi.co/api/v2/pokemon-species/3/"
+            ]
+        );
+        let species = NamedNodeRef::new("https://pokeapi.co/api/v2/pokemon-species/1/").unwrap();
+        let color = NamedNodeRef::new("https://pokeapi.co/api/v2/pokemon-color/5/").unwrap();
+        assert!(graph.triples_for_subject(species).next().is_some());
+        assert!(graph.triples_for_subject(color).next().is_some());
+        // nothing two links away, e.g. the species' pokemon
+        let pokemon = NamedNodeRef::new("https://pokeapi.co/api/v2/pokemon/1/").unwrap();
+        assert!(graph.triples_for_subject(pokemon).next().is_none());
+    }
+}
@@ -0,0 +1,380 @@
+use http_cache_reqwest::{CACacheManager, Cache, CacheMode, HttpCache, HttpCacheOptions};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware, Middleware, Next};
+use rustemon::model::resource::{
+    ApiResource, ApiResourceList, NamedApiResource, NamedApiResourceList,
+};
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::Error;
+use crate::POKEAPI;
+
+/// User-Agent sent with every request unless configured otherwise.
+pub static USER_AGENT: &str = concat!("pokemon2rdf/", env!("CARGO_PKG_VERSION"));
+
+/// How PokeAPI is accessed: where it is, request rate, retries and the User-Agent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientConfig {
+    /// Requests per second across all collections, cache hits do not count.
+    pub requests_per_second: f64,
+    /// How often a failed request is retried when the failure may be transient.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for every further one.
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between retries.
+    pub max_backoff: Duration,
+    pub user_agent: String,
+    /// Where requests for `https://pokeapi.co/api/v2/` are sent, e.g. a local PokeAPI instance.
+    /// Ends with `/`. Resources keep their `https://pokeapi.co/api/v2/` IRIs in the graph.
+    pub base_url: String,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        ClientConfig {
+            requests_per_second: 10.0,
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            user_agent: USER_AGENT.to_string(),
+            base_url: POKEAPI.to_string(),
+        }
+    }
+}
+
+/// PokeAPI client shared by all collections. Responses are cached on disk in `rustemon-cache`,
+/// requests that reach the API are rate limited and transient failures are retried.
+#[derive(Debug)]
+pub struct Client {
+    http: ClientWithMiddleware,
+    config: ClientConfig,
+    // bodies fetched by `json_hash` that `follow` has not taken yet
+    fetched: std::sync::Mutex<HashMap<String, Vec<u8>>>,
+    // directory with recorded responses that stands in for the API in tests
+    fixtures: Option<PathBuf>,
+}
+
+impl Client {
+    pub fn new(config: ClientConfig) -> Result<Self, Error> {
+        let http = reqwest::Client::builder()
+            .user_agent(&config.user_agent)
+            .build()
+            .map_err(Error::Client)?;
+        let cache = Cache(HttpCache {
+            mode: CacheMode::Default,
+            manager: CACacheManager::new("./rustemon-cache".into(), false),
+            options: HttpCacheOptions::default(),
+        });
+        // the cache comes first, so only requests that reach the API wait for the limiter
+        let http = ClientBuilder::new(http)
+            .with(cache)
+            .with(RateLimit::new(config.requests_per_second))
+            .build();
+        Ok(Client {
+            http,
+            config,
+            fetched: Default::default(),
+            fixtures: None,
+        })
+    }
+
+    // Answers every request from `dir` instead of PokeAPI, see tests/fixtures/README.md
+    #[cfg(test)]
+    pub(crate) fn with_fixtures(dir: impl Into<PathBuf>) -> Self {
+        Client {
+            fixtures: Some(dir.into()),
+            ..Client::default()
+        }
+    }
+
+    // GET and deserialize a PokeAPI resource, retrying transient failures
+    pub(crate) async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T, Error> {
+        let body = self.get_body(url).await?;
+        parse(url, &body)
+    }
+
+    async fn get_body(&self, url: &str) -> Result<Vec<u8>, Error> {
+        let mut attempt = 0;
+        loop {
+            match self.try_get_body(url).await {
+                Err(e) if e.is_retryable() && attempt < self.config.max_retries => {
+                    tokio::time::sleep(self.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn try_get_body(&self, url: &str) -> Result<Vec<u8>, Error> {
+        if let Some(dir) = &self.fixtures {
+            return read_fixture(dir, url);
+        }
+        let response = self
+            .http
+            .get(self.request_url(url))
+            .send()
+            .await
+            .map_err(|e| Error::fetch(url, e))?;
+        let body = response
+            .error_for_status()
+            .map_err(|e| Error::fetch(url, e))?
+            .bytes()
+            .await
+            .map_err(|e| Error::fetch(url, e))?;
+        Ok(body.to_vec())
+    }
+
+    // The URL a PokeAPI resource is requested from, errors keep reporting the canonical one
+    fn request_url(&self, url: &str) -> String {
+        match url.strip_prefix(POKEAPI) {
+            Some(path) => format!("{}{}", self.config.base_url, path),
+            None => url.to_string(),
+        }
+    }
+
+    // Exponential backoff with jitter, between half and the full delay
+    fn backoff(&self, attempt: u32) -> Duration {
+        let delay = self
+            .config
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.config.max_backoff);
+        delay / 2 + delay.mul_f64(fastrand::f64() / 2.0)
+    }
+
+    // All entries of a named endpoint, e.g. "pokemon-species"
+    pub(crate) async fn get_all_entries<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+    ) -> Result<Vec<NamedApiResource<T>>, Error> {
+        let first: NamedApiResourceList<T> = self.get(&format!("{POKEAPI}{endpoint}/")).await?;
+        let mut entries = first.results;
+        let remaining = first.count - entries.len() as i64;
+        if remaining > 0 {
+            let url = format!(
+                "{POKEAPI}{endpoint}/?offset={}&limit={}",
+                entries.len(),
+                remaining
+            );
+            let rest: NamedApiResourceList<T> = self.get(&url).await?;
+            entries.extend(rest.results);
+        }
+        Ok(entries)
+    }
+
+    // All entries of an endpoint whose resources have no name, e.g. "machine"
+    pub(crate) async fn get_all_unnamed_entries<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+    ) -> Result<Vec<ApiResource<T>>, Error> {
+        let first: ApiResourceList<T> = self.get(&format!("{POKEAPI}{endpoint}/")).await?;
+        let mut entries = first.results;
+        let remaining = first.count - entries.len() as i64;
+        if remaining > 0 {
+            let url = format!(
+                "{POKEAPI}{endpoint}/?offset={}&limit={}",
+                entries.len(),
+                remaining
+            );
+            let rest: ApiResourceList<T> = self.get(&url).await?;
+            entries.extend(rest.results);
+        }
+        Ok(entries)
+    }
+
+    // Fetches the resource a converter is built from, the body `json_hash` fetched if there is one
+    pub(crate) async fn follow<T: DeserializeOwned>(&self, url: &str) -> Result<T, Error> {
+        let body = self.take_body(url).await?;
+        parse(url, &body)
+    }
+
+    // Like `follow`, also returning the JSON as is for fields the rustemon model does not have
+    pub(crate) async fn follow_raw<T: DeserializeOwned>(
+        &self,
+        url: &str,
+    ) -> Result<(T, serde_json::Value), Error> {
+        let body = self.take_body(url).await?;
+        Ok((parse(url, &body)?, parse(url, &body)?))
+    }
+
+    async fn take_body(&self, url: &str) -> Result<Vec<u8>, Error> {
+        let fetched = self.fetched.lock().unwrap().remove(url);
+        match fetched {
+            Some(body) => Ok(body),
+            None => self.get_body(url).await,
+        }
+    }
+
+    // sha256 of the JSON of a resource and of the resources its `links` fields point to, whose
+    // bodies are kept for the `follow`s that convert them. Returns the hash and the fetched URLs.
+    pub(crate) async fn json_hash(
+        &self,
+        url: &str,
+        links: &[&str],
+    ) -> Result<(String, Vec<String>), Error> {
+        let body = self.get_body(url).await?;
+        let mut hasher = Sha256::new();
+        hasher.update(&body);
+        let mut bodies = vec![(url.to_string(), body)];
+        if !links.is_empty() {
+            let json: serde_json::Value = parse(url, &bodies[0].1)?;
+            for link in links {
+                if let Some(linked) = json[link].as_str() {
+                    let body = self.get_body(linked).await?;
+                    hasher.update(&body);
+                    bodies.push((linked.to_string(), body));
+                }
+            }
+        }
+        let urls = bodies.iter().map(|(url, _)| url.clone()).collect();
+        self.fetched.lock().unwrap().extend(bodies);
+        Ok((format!("{:x}", hasher.finalize()), urls))
+    }
+
+    // Drops the bodies `json_hash` kept when the resource was not converted after all
+    pub(crate) fn forget(&self, urls: &[String]) {
+        let mut fetched = self.fetched.lock().unwrap();
+        for url in urls {
+            fetched.remove(url);
+        }
+    }
+}
+
+// `{POKEAPI}pokemon/1/` is answered by `pokemon/1.json`, the list `{POKEAPI}pokemon/` by
+// `pokemon.json`. Paging parameters are ignored, a list fixture holds the whole list.
+fn read_fixture(dir: &std::path::Path, url: &str) -> Result<Vec<u8>, Error> {
+    let path = url
+        .strip_prefix(POKEAPI)
+        .map(|path| path.split('?').next().unwrap_or_default())
+        .map(|path| dir.join(format!("{}.json", path.trim_end_matches('/'))))
+        .ok_or_else(|| Error::InvalidData(format!("{url} is not a PokeAPI URL")))?;
+    std::fs::read(&path).map_err(|e| {
+        Error::fetch(
+            url,
+            reqwest_middleware::Error::middleware(Error::io(
+                format!("No fixture {}", path.display()),
+                e,
+            )),
+        )
+    })
+}
+
+fn parse<T: DeserializeOwned>(url: &str, body: &[u8]) -> Result<T, Error> {
+    serde_json::from_slice(body).map_err(|source| Error::Decode {
+        url: url.to_string(),
+        source,
+    })
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Client::new(ClientConfig::default()).expect("default client configuration is valid")
+    }
+}
+
+// Spaces requests evenly, each request reserves the next free slot so concurrent requests
+// queue up instead of bursting
+#[derive(Debug)]
+struct RateLimit {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimit {
+    fn new(requests_per_second: f64) -> Self {
+        let interval = if requests_per_second > 0.0 {
+            Duration::from_secs_f64(1.0 / requests_per_second)
+        } else {
+            Duration::ZERO
+        };
+        RateLimit {
+            interval,
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    async fn wait(&self) {
+        let slot = {
+            let mut next = self.next.lock().await;
+            let slot = (*next).max(Instant::now());
+            *next = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for RateLimit {
+    async fn handle(
+        &self,
+        req: reqwest::Request,
+        extensions: &mut http::Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        self.wait().await;
+        next.run(req, extensions).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_concurrent() {
+        let limit = std::sync::Arc::new(RateLimit::new(4.0));
+        let start = Instant::now();
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let limit = limit.clone();
+                tokio::spawn(async move {
+                    limit.wait().await;
+                    Instant::now()
+                })
+            })
+            .collect();
+        let mut times = vec![];
+        for handle in handles {
+            times.push(handle.await.unwrap() - start);
+        }
+        times.sort();
+        // 8 requests at 4 per second, the last one goes out after 1.75s
+        assert_eq!(times.first(), Some(&Duration::ZERO));
+        assert_eq!(times.last(), Some(&Duration::from_millis(1750)));
+    }
+
+    // A malformed body is a decode error of that resource, not a failed request
+    #[test]
+    fn test_decode_error() {
+        let url = "https://pokeapi.co/api/v2/pokemon/1/";
+        let error = parse::<Vec<i64>>(url, b"{\"id\": 1").unwrap_err();
+        assert!(matches!(&error, Error::Decode { url: u, .. } if u == url));
+        assert!(!error.is_retryable());
+        let failures = crate::failures::Failures::new(crate::FailureMode::Skip);
+        failures.skip_failed("pokemon", Err(error)).unwrap();
+        assert_eq!(failures.into_inner()[0].url, url);
+    }
+
+    #[test]
+    fn test_backoff() {
+        let client = Client::new(ClientConfig {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+            ..ClientConfig::default()
+        })
+        .unwrap();
+        for attempt in 0..6 {
+            let delay = Duration::from_secs(1 << attempt).min(Duration::from_secs(5));
+            let backoff = client.backoff(attempt);
+            assert!(backoff >= delay / 2 && backoff <= delay, "{backoff:?}");
+        }
+    }
+}
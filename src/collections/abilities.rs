@@ -1,130 +1,172 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
-use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use oxrdf::{BlankNode, Literal, NamedNode, NamedNodeRef, Triple};
+use rustemon::model::pokemon::Ability;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
-use crate::create_type_triple;
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
 use crate::POKEMONKG;
 use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
 
 pub async fn ability_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let all_abilities = match rustemon::pokemon::ability::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_abilities = match client.get_all_entries::<Ability>("ability").await {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all abilities: {:?}", e);
-            return Err(e.into());
+            return failures.record("ability", e);
         }
     };
     let len = all_abilities.len();
-    let pb = bar.add(
-        ProgressBar::new(len.try_into().unwrap())
-            .with_style(crate::create_bar_style()),
-    );
+    let pb =
+        bar.add(ProgressBar::new(len.try_into().unwrap()).with_style(crate::create_bar_style()));
     pb.finish_with_message("done");
     for (index, p) in all_abilities.into_iter().enumerate() {
         pb.set_message(format!("ability {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples: Vec<Triple> = vec![];
-        let ability_id = NamedNodeRef::new(p.url.as_str())?;
-        let ability_json = match p.follow(&client).await {
-            Ok(list) => list,
-            Err(e) => {
-                println!("error getting ability info for {}: {e}", &p.url);
-                return Err(e.into());
-            }
-        };
-        // Add rdf:type declaration
-        triples.push(create_type_triple(ability_id, "Ability")?);
+        failures.skip_failed(
+            "ability",
+            Collection::Ability
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
 
-        triples.push(Triple {
-            subject: ability_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(ability_json.name.clone()).into(),
-        });
-        triples.push(Triple {
-            subject: ability_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(ability_json.id.to_string(), xsd::INTEGER).into(),
-        });
+    Ok(())
+}
 
-        // TODO is_main_series
+/// Converts the ability at `url`, e.g. `https://pokeapi.co/api/v2/ability/1/`.
+pub async fn ability_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let ability_id = NamedNodeRef::new(url)?;
+    let ability_json: Ability = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(ability_id, "Ability")?);
 
-        // generation
-        let gen_id = NamedNodeRef::new(&ability_json.generation.url)?;
-        triples.push(Triple {
-            subject: ability_id.into(),
-            predicate: NamedNode::new(format!("{POKE}generation"))?,
-            object: gen_id.into(),
-        });
+    triples.push(Triple {
+        subject: ability_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(ability_json.name.clone()).into(),
+    });
+    triples.push(Triple {
+        subject: ability_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(ability_json.id.to_string(), xsd::INTEGER).into(),
+    });
 
-        for v in ability_json.effect_entries {
-            // TODO only do english for now
-            if v.language.name == "en" {
-                triples.push(Triple {
-                    subject: ability_id.into(),
-                    predicate: NamedNode::new(format!("{POKEMONKG}effectDescription"))?,
-                    object: Literal::new_simple_literal(v.effect).into(),
-                });
-                triples.push(Triple {
-                    subject: ability_id.into(),
-                    predicate: NamedNode::new(format!("{POKEMONKG}effectDescription"))?,
-                    object: Literal::new_simple_literal(v.short_effect).into(),
-                });
-            }
-        }
+    // TODO is_main_series
 
-        // TODO effect_changes
+    // generation
+    let gen_id = NamedNodeRef::new(&ability_json.generation.url)?;
+    triples.push(Triple {
+        subject: ability_id.into(),
+        predicate: NamedNode::new(format!("{POKE}generation"))?,
+        object: gen_id.into(),
+    });
 
-        for v in ability_json.flavor_text_entries {
+    for v in ability_json.effect_entries {
+        // TODO only do english for now
+        if v.language.name == "en" {
+            triples.extend(localized_to_nt(
+                ability_id.into(),
+                NamedNode::new(format!("{POKEMONKG}effectDescription"))?,
+                v.effect,
+                &v.language,
+            )?);
+            triples.extend(localized_to_nt(
+                ability_id.into(),
+                NamedNode::new(format!("{POKEMONKG}effectDescription"))?,
+                v.short_effect,
+                &v.language,
+            )?);
+        }
+    }
+
+    // effect_changes, the effect the ability had before it was changed in the given
+    // version group. The current effect is the one directly on the ability.
+    for (i, change) in ability_json.effect_changes.into_iter().enumerate() {
+        let change_id = BlankNode::new(format!("ability{}_effectchange{}", ability_json.id, i))?;
+        triples.push(Triple {
+            subject: ability_id.into(),
+            predicate: NamedNode::new(format!("{POKE}effectChange"))?,
+            object: change_id.as_ref().into(),
+        });
+        triples.push(create_type_triple(
+            change_id.as_ref(),
+            "AbilityEffectChange",
+        )?);
+        triples.push(Triple {
+            subject: change_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}changedInVersionGroup"))?,
+            object: NamedNode::new(change.version_group.url)?.into(),
+        });
+        for effect in change.effect_entries {
             // TODO only do english for now
-            if v.language.name == "en" {
-                triples.push(Triple {
-                    subject: ability_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}flavorText"))?,
-                    object: Literal::new_simple_literal(v.flavor_text).into(),
-                });
+            if effect.language.name == "en" {
+                triples.extend(localized_to_nt(
+                    change_id.as_ref().into(),
+                    NamedNode::new(format!("{POKEMONKG}effectDescription"))?,
+                    effect.effect,
+                    &effect.language,
+                )?);
             }
         }
+    }
 
-        for pokemon in ability_json.pokemon {
-            triples.push(Triple {
-                subject: ability_id.into(),
-                predicate: NamedNode::new(format!("{POKE}mayBeFoundInPokemon"))?,
-                object: NamedNodeRef::new(pokemon.pokemon.url.as_str())?.into(),
-            });
+    for v in ability_json.flavor_text_entries {
+        // TODO only do english for now
+        if v.language.name == "en" {
+            triples.extend(localized_to_nt(
+                ability_id.into(),
+                NamedNode::new(format!("{POKE}flavorText"))?,
+                v.flavor_text,
+                &v.language,
+            )?);
         }
+    }
 
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
-        }
+    for pokemon in ability_json.pokemon {
+        triples.push(Triple {
+            subject: ability_id.into(),
+            predicate: NamedNode::new(format!("{POKE}mayBeFoundInPokemon"))?,
+            object: NamedNodeRef::new(pokemon.pokemon.url.as_str())?.into(),
+        });
     }
 
-    Ok(())
+    send_resource(sink, url, triples)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_abilities() {
-        assert!((ability_to_nt(
+        let mut graph = Graph::new();
+        ability_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("abilities", &graph);
     }
 }
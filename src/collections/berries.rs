@@ -1,26 +1,28 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
 use oxrdf::{BlankNode, Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use rustemon::model::berries::Berry;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
 use crate::SCHEMA;
 use crate::{create_bar_style, create_type_triple};
 
 pub async fn berry_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let all_berries = match rustemon::berries::berry::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_berries = match client.get_all_entries::<Berry>("berry").await {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all berries: {:?}", e);
-            return Err(e.into());
+            return failures.record("berry", e);
         }
     };
     let len = all_berries.len();
@@ -29,121 +31,125 @@ pub async fn berry_to_nt(
     for (index, p) in all_berries.into_iter().enumerate() {
         pb.set_message(format!("berries {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples: Vec<Triple> = vec![];
-        let berry_id = NamedNodeRef::new(p.url.as_str())?;
-        let berry_json = match p.follow(&client).await {
-            Ok(list) => list,
-            Err(e) => {
-                println!("error getting berry info for {}: {e}", &p.url);
-                return Err(e.into());
-            }
-        };
-        // Add rdf:type declaration
-        triples.push(create_type_triple(berry_id, "Berry")?);
+        failures.skip_failed(
+            "berry",
+            Collection::Berry
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+    Ok(())
+}
 
-        triples.push(Triple {
-            subject: berry_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(berry_json.id.to_string(), xsd::INTEGER).into(),
-        });
-        triples.push(Triple {
-            subject: berry_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(berry_json.name).into(),
-        });
-        triples.push(Triple {
-            subject: berry_id.into(),
-            predicate: NamedNode::new(format!("{POKE}growthTime"))?,
-            object: Literal::new_typed_literal(berry_json.growth_time.to_string(), xsd::INTEGER)
-                .into(),
-        });
-        triples.push(Triple {
-            subject: berry_id.into(),
-            predicate: NamedNode::new(format!("{POKE}maxHarvest"))?,
-            object: Literal::new_typed_literal(berry_json.max_harvest.to_string(), xsd::INTEGER)
-                .into(),
-        });
-        triples.push(Triple {
-            subject: berry_id.into(),
-            predicate: NamedNode::new(format!("{POKE}naturalGiftPower"))?,
-            object: Literal::new_typed_literal(
-                berry_json.natural_gift_power.to_string(),
-                xsd::INTEGER,
-            )
+/// Converts the berry at `url`, e.g. `https://pokeapi.co/api/v2/berry/1/`.
+pub async fn berry_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let berry_id = NamedNodeRef::new(url)?;
+    let berry_json: Berry = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(berry_id, "Berry")?);
+
+    triples.push(Triple {
+        subject: berry_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(berry_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: berry_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(berry_json.name).into(),
+    });
+    triples.push(Triple {
+        subject: berry_id.into(),
+        predicate: NamedNode::new(format!("{POKE}growthTime"))?,
+        object: Literal::new_typed_literal(berry_json.growth_time.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: berry_id.into(),
+        predicate: NamedNode::new(format!("{POKE}maxHarvest"))?,
+        object: Literal::new_typed_literal(berry_json.max_harvest.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: berry_id.into(),
+        predicate: NamedNode::new(format!("{POKE}naturalGiftPower"))?,
+        object: Literal::new_typed_literal(berry_json.natural_gift_power.to_string(), xsd::INTEGER)
             .into(),
-        });
-        triples.push(Triple {
-            subject: berry_id.into(),
-            predicate: NamedNode::new(format!("{POKE}size"))?,
-            object: Literal::new_typed_literal(berry_json.size.to_string(), xsd::INTEGER).into(),
-        });
-        triples.push(Triple {
-            subject: berry_id.into(),
-            predicate: NamedNode::new(format!("{POKE}smoothness"))?,
-            object: Literal::new_typed_literal(berry_json.smoothness.to_string(), xsd::INTEGER)
-                .into(),
-        });
-        triples.push(Triple {
-            subject: berry_id.into(),
-            predicate: NamedNode::new(format!("{POKE}soilDryness"))?,
-            object: Literal::new_typed_literal(berry_json.soil_dryness.to_string(), xsd::INTEGER)
-                .into(),
-        });
+    });
+    triples.push(Triple {
+        subject: berry_id.into(),
+        predicate: NamedNode::new(format!("{POKE}size"))?,
+        object: Literal::new_typed_literal(berry_json.size.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: berry_id.into(),
+        predicate: NamedNode::new(format!("{POKE}smoothness"))?,
+        object: Literal::new_typed_literal(berry_json.smoothness.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: berry_id.into(),
+        predicate: NamedNode::new(format!("{POKE}soilDryness"))?,
+        object: Literal::new_typed_literal(berry_json.soil_dryness.to_string(), xsd::INTEGER)
+            .into(),
+    });
+    triples.push(Triple {
+        subject: berry_id.into(),
+        predicate: NamedNode::new(format!("{POKE}firmness"))?,
+        object: NamedNode::new(berry_json.firmness.url)?.into(),
+    });
+    for (i, f) in berry_json.flavors.into_iter().enumerate() {
+        let flavor_id = BlankNode::new(format!("berry{}_flavor{}", berry_json.id, i))?;
         triples.push(Triple {
             subject: berry_id.into(),
-            predicate: NamedNode::new(format!("{POKE}firmness"))?,
-            object: NamedNode::new(berry_json.firmness.url)?.into(),
+            predicate: NamedNode::new(format!("{POKE}hasFlavor"))?,
+            object: flavor_id.as_ref().into(),
         });
-        for (i, f) in berry_json.flavors.into_iter().enumerate() {
-            let flavor_id = BlankNode::new(format!("berry{}_flavor{}", berry_json.id, i))?;
-            triples.push(Triple {
-                subject: berry_id.into(),
-                predicate: NamedNode::new(format!("{POKE}hasFlavor"))?,
-                object: flavor_id.as_ref().into(),
-            });
-            triples.push(Triple {
-                subject: flavor_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{POKE}flavor"))?,
-                object: NamedNode::new(f.flavor.url)?.into(),
-            });
-            triples.push(Triple {
-                subject: flavor_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{POKE}potency"))?,
-                object: Literal::new_typed_literal(f.potency.to_string(), xsd::INTEGER).into(),
-            });
-        }
         triples.push(Triple {
-            subject: berry_id.into(),
-            predicate: NamedNode::new(format!("{POKE}item"))?,
-            object: NamedNode::new(berry_json.item.url)?.into(),
+            subject: flavor_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}flavor"))?,
+            object: NamedNode::new(f.flavor.url)?.into(),
         });
         triples.push(Triple {
-            subject: berry_id.into(),
-            predicate: NamedNode::new(format!("{POKE}naturalGiftType"))?,
-            object: NamedNode::new(berry_json.natural_gift_type.url)?.into(),
+            subject: flavor_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}potency"))?,
+            object: Literal::new_typed_literal(f.potency.to_string(), xsd::INTEGER).into(),
         });
-
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
-        }
     }
-    Ok(())
+    triples.push(Triple {
+        subject: berry_id.into(),
+        predicate: NamedNode::new(format!("{POKE}item"))?,
+        object: NamedNode::new(berry_json.item.url)?.into(),
+    });
+    triples.push(Triple {
+        subject: berry_id.into(),
+        predicate: NamedNode::new(format!("{POKE}naturalGiftType"))?,
+        object: NamedNode::new(berry_json.natural_gift_type.url)?.into(),
+    });
+
+    send_resource(sink, url, triples)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_berry() {
-        assert!((berry_to_nt(
+        let mut graph = Graph::new();
+        berry_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("berries", &graph);
     }
 }
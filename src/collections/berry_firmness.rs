@@ -1,26 +1,31 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
 use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use rustemon::model::berries::BerryFirmness;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
 use crate::SCHEMA;
-use crate::{create_bar_style, create_type_triple};
+use crate::{create_bar_style, create_type_triple, localized_to_nt};
 
 pub async fn firmness_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let all_firmnesses = match rustemon::berries::berry_firmness::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_firmnesses = match client
+        .get_all_entries::<BerryFirmness>("berry-firmness")
+        .await
+    {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all berry firmnesses: {:?}", e);
-            return Err(e.into());
+            return failures.record("berry-firmness", e);
         }
     };
     let len = all_firmnesses.len();
@@ -29,68 +34,79 @@ pub async fn firmness_to_nt(
     for (index, p) in all_firmnesses.into_iter().enumerate() {
         pb.set_message(format!("berry firmnesses {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples: Vec<Triple> = vec![];
-        let berry_id = NamedNodeRef::new(p.url.as_str())?;
-        let berry_json = match p.follow(&client).await {
-            Ok(list) => list,
-            Err(e) => {
-                println!("error getting berry info for {}: {e}", &p.url);
-                return Err(e.into());
-            }
-        };
-        // Add rdf:type declaration
-        triples.push(create_type_triple(berry_id, "Berry")?);
+        failures.skip_failed(
+            "berry-firmness",
+            Collection::BerryFirmness
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+    Ok(())
+}
 
+/// Converts the berry firmness at `url`, e.g. `https://pokeapi.co/api/v2/berry-firmness/1/`.
+pub async fn firmness_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let berry_id = NamedNodeRef::new(url)?;
+    let berry_json: BerryFirmness = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(berry_id, "Berry")?);
+
+    triples.push(Triple {
+        subject: berry_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(berry_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: berry_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(berry_json.name).into(),
+    });
+    for berry in berry_json.berries {
         triples.push(Triple {
             subject: berry_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(berry_json.id.to_string(), xsd::INTEGER).into(),
-        });
-        triples.push(Triple {
-            subject: berry_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(berry_json.name).into(),
+            predicate: NamedNode::new(format!("{POKE}hasBerry"))?,
+            object: NamedNode::new(berry.url)?.into(),
         });
-        for berry in berry_json.berries {
-            triples.push(Triple {
-                subject: berry_id.into(),
-                predicate: NamedNode::new(format!("{POKE}hasBerry"))?,
-                object: NamedNode::new(berry.url)?.into(),
-            });
-        }
-
-        for name in berry_json.names {
-            // Only include English names for now
-            if name.language.name == "en" {
-                triples.push(Triple {
-                    subject: berry_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}names"))?,
-                    object: Literal::new_simple_literal(name.name).into(),
-                });
-            }
-        }
+    }
 
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
+    for name in berry_json.names {
+        // Only include English names for now
+        if name.language.name == "en" {
+            triples.extend(localized_to_nt(
+                berry_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                name.name,
+                &name.language,
+            )?);
         }
     }
-    Ok(())
+
+    send_resource(sink, url, triples)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_firm() {
-        assert!((firmness_to_nt(
+        let mut graph = Graph::new();
+        firmness_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("berry_firmness", &graph);
     }
 }
@@ -0,0 +1,124 @@
+use indicatif::{MultiProgress, ProgressBar};
+use oxrdf::vocab::xsd;
+use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
+use rustemon::model::pokemon::Characteristic;
+use std::sync::Arc;
+
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
+use crate::POKE;
+use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
+
+pub async fn characteristic_to_nt(
+    bar: &MultiProgress,
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_characteristics = match client
+        .get_all_unnamed_entries::<Characteristic>("characteristic")
+        .await
+    {
+        Ok(list) => list,
+        Err(e) => {
+            return failures.record("characteristic", e);
+        }
+    };
+    let len = all_characteristics.len();
+    let pb =
+        bar.add(ProgressBar::new(len.try_into().unwrap()).with_style(crate::create_bar_style()));
+    pb.finish_with_message("done");
+    for (index, p) in all_characteristics.into_iter().enumerate() {
+        pb.set_message(format!("characteristic {}/{}", index + 1, len));
+        pb.inc(1);
+        failures.skip_failed(
+            "characteristic",
+            Collection::Characteristic
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Converts the characteristic at `url`, e.g. `https://pokeapi.co/api/v2/characteristic/1/`.
+pub async fn characteristic_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let characteristic_id = NamedNodeRef::new(url)?;
+    let characteristic_json: Characteristic = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(characteristic_id, "Characteristic")?);
+
+    triples.push(Triple {
+        subject: characteristic_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(characteristic_json.id.to_string(), xsd::INTEGER).into(),
+    });
+
+    triples.push(Triple {
+        subject: characteristic_id.into(),
+        predicate: NamedNode::new(format!("{POKE}geneModulo"))?,
+        object: Literal::new_typed_literal(
+            characteristic_json.gene_modulo.to_string(),
+            xsd::INTEGER,
+        )
+        .into(),
+    });
+    for value in characteristic_json.possible_values {
+        triples.push(Triple {
+            subject: characteristic_id.into(),
+            predicate: NamedNode::new(format!("{POKE}possibleValue"))?,
+            object: Literal::new_typed_literal(value.to_string(), xsd::INTEGER).into(),
+        });
+    }
+    triples.push(Triple {
+        subject: characteristic_id.into(),
+        predicate: NamedNode::new(format!("{POKE}highestStat"))?,
+        object: NamedNode::new(characteristic_json.highest_stat.url)?.into(),
+    });
+
+    for d in characteristic_json.descriptions {
+        // TODO only english for now
+        if d.language.name == "en" {
+            triples.extend(localized_to_nt(
+                characteristic_id.into(),
+                NamedNode::new(format!("{SCHEMA}description"))?,
+                d.description,
+                &d.language,
+            )?);
+        }
+    }
+
+    send_resource(sink, url, triples)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
+
+    #[tokio::test]
+    async fn test_characteristics() {
+        let mut graph = Graph::new();
+        characteristic_to_nt(
+            &MultiProgress::new(),
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
+        )
+        .await
+        .unwrap();
+        fixtures::assert_golden("characteristics", &graph);
+    }
+}
@@ -0,0 +1,115 @@
+use indicatif::{MultiProgress, ProgressBar};
+use oxrdf::vocab::xsd;
+use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
+use rustemon::model::pokemon::PokemonColor;
+use std::sync::Arc;
+
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
+use crate::POKE;
+use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
+
+pub async fn color_to_nt(
+    bar: &MultiProgress,
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_colors = match client
+        .get_all_entries::<PokemonColor>("pokemon-color")
+        .await
+    {
+        Ok(list) => list,
+        Err(e) => {
+            return failures.record("pokemon-color", e);
+        }
+    };
+    let len = all_colors.len();
+    let pb =
+        bar.add(ProgressBar::new(len.try_into().unwrap()).with_style(crate::create_bar_style()));
+    pb.finish_with_message("done");
+    for (index, p) in all_colors.into_iter().enumerate() {
+        pb.set_message(format!("color {}/{}", index + 1, len));
+        pb.inc(1);
+        failures.skip_failed(
+            "pokemon-color",
+            Collection::PokemonColor
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Converts the pokemon color at `url`, e.g. `https://pokeapi.co/api/v2/pokemon-color/1/`.
+pub async fn color_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let color_id = NamedNodeRef::new(url)?;
+    let color_json: PokemonColor = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(color_id, "PokemonColor")?);
+
+    triples.push(Triple {
+        subject: color_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(color_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: color_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(color_json.name).into(),
+    });
+
+    for name in color_json.names {
+        // TODO only english for now
+        if name.language.name == "en" {
+            triples.extend(localized_to_nt(
+                color_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                name.name,
+                &name.language,
+            )?);
+        }
+    }
+
+    for species in color_json.pokemon_species {
+        triples.push(Triple {
+            subject: color_id.into(),
+            predicate: NamedNode::new(format!("{POKE}species"))?,
+            object: NamedNode::new(species.url)?.into(),
+        });
+    }
+
+    send_resource(sink, url, triples)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
+
+    #[tokio::test]
+    async fn test_colors() {
+        let mut graph = Graph::new();
+        color_to_nt(
+            &MultiProgress::new(),
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
+        )
+        .await
+        .unwrap();
+        fixtures::assert_golden("colors", &graph);
+    }
+}
@@ -1,25 +1,30 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
 use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use rustemon::model::moves::MoveDamageClass;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::SCHEMA;
-use crate::{create_bar_style, create_type_triple};
+use crate::{create_bar_style, create_type_triple, localized_to_nt};
 
 pub async fn damage_class_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let all_damages = match rustemon::moves::move_damage_class::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_damages = match client
+        .get_all_entries::<MoveDamageClass>("move-damage-class")
+        .await
+    {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all damage classes: {:?}", e);
-            return Err(e.into());
+            return failures.record("move-damage-class", e);
         }
     };
     let len = all_damages.len();
@@ -28,61 +33,72 @@ pub async fn damage_class_to_nt(
     for (index, p) in all_damages.into_iter().enumerate() {
         pb.set_message(format!("move damage class {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples: Vec<Triple> = vec![];
-        let damage_id = NamedNodeRef::new(p.url.as_str())?;
-        let damage_json = match p.follow(&client).await {
-            Ok(list) => list,
-            Err(e) => {
-                println!("error getting damage class info for {}: {e}", &p.url);
-                return Err(e.into());
-            }
-        };
-        // Add rdf:type declaration
-        triples.push(create_type_triple(damage_id, "MoveDamageClass")?);
+        failures.skip_failed(
+            "move-damage-class",
+            Collection::MoveDamageClass
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
 
-        triples.push(Triple {
-            subject: damage_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(p.name.clone()).into(),
-        });
+    Ok(())
+}
 
-        triples.push(Triple {
-            subject: damage_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(damage_json.id.to_string(), xsd::INTEGER).into(),
-        });
-        for d in damage_json.descriptions.clone() {
-            // TODO only english for now
-            if d.language.name == "en" {
-                triples.push(Triple {
-                    subject: damage_id.into(),
-                    predicate: NamedNode::new(format!("{SCHEMA}description"))?,
-                    object: Literal::new_simple_literal(d.description).into(),
-                });
-            }
-        }
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
+/// Converts the move damage class at `url`, e.g. `https://pokeapi.co/api/v2/move-damage-class/1/`.
+pub async fn damage_class_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let damage_id = NamedNodeRef::new(url)?;
+    let damage_json: MoveDamageClass = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(damage_id, "MoveDamageClass")?);
+
+    triples.push(Triple {
+        subject: damage_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(damage_json.name.clone()).into(),
+    });
+
+    triples.push(Triple {
+        subject: damage_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(damage_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    for d in damage_json.descriptions.clone() {
+        // TODO only english for now
+        if d.language.name == "en" {
+            triples.extend(localized_to_nt(
+                damage_id.into(),
+                NamedNode::new(format!("{SCHEMA}description"))?,
+                d.description,
+                &d.language,
+            )?);
         }
     }
-
-    Ok(())
+    send_resource(sink, url, triples)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_damage_classes() {
-        assert!((damage_class_to_nt(
+        let mut graph = Graph::new();
+        damage_class_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("damage_class", &graph);
     }
 }
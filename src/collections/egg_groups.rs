@@ -1,26 +1,28 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
 use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use rustemon::model::pokemon::EggGroup;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
-use crate::create_type_triple;
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
 use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
 
 pub async fn egg_group_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let egg_groups = match rustemon::pokemon::egg_group::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let egg_groups = match client.get_all_entries::<EggGroup>("egg-group").await {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all egg groups: {:?}", e);
-            return Err(e.into());
+            return failures.record("egg-group", e);
         }
     };
     let len = egg_groups.len();
@@ -30,68 +32,79 @@ pub async fn egg_group_to_nt(
     for (index, p) in egg_groups.into_iter().enumerate() {
         pb.set_message(format!("egg group {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples: Vec<Triple> = vec![];
-        let group_id = NamedNodeRef::new(p.url.as_str())?;
-        let group_json = match p.follow(&client).await {
-            Ok(list) => list,
-            Err(e) => {
-                println!("error getting egg group info for {}: {e}", &p.url);
-                return Err(e.into());
-            }
-        };
-        // Add rdf:type declaration
-        triples.push(create_type_triple(group_id, "EggGroup")?);
+        failures.skip_failed(
+            "egg-group",
+            Collection::EggGroup
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+    Ok(())
+}
 
-        triples.push(Triple {
-            subject: group_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(group_json.id.to_string(), xsd::INTEGER).into(),
-        });
-        triples.push(Triple {
-            subject: group_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(group_json.name).into(),
-        });
-        for name in group_json.names {
-            // TODO only english for now
-            if name.language.name == "en" {
-                triples.push(Triple {
-                    subject: group_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}names"))?,
-                    object: Literal::new_simple_literal(name.name).into(),
-                });
-            }
-        }
+/// Converts the egg group at `url`, e.g. `https://pokeapi.co/api/v2/egg-group/1/`.
+pub async fn egg_group_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let group_id = NamedNodeRef::new(url)?;
+    let group_json: EggGroup = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(group_id, "EggGroup")?);
 
-        for species in group_json.pokemon_species {
-            triples.push(Triple {
-                subject: group_id.into(),
-                predicate: NamedNode::new(format!("{POKE}hasPokemonSpecies"))?,
-                object: NamedNodeRef::new(species.url.as_str())?.into(),
-            });
+    triples.push(Triple {
+        subject: group_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(group_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: group_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(group_json.name).into(),
+    });
+    for name in group_json.names {
+        // TODO only english for now
+        if name.language.name == "en" {
+            triples.extend(localized_to_nt(
+                group_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                name.name,
+                &name.language,
+            )?);
         }
+    }
 
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
-        }
+    for species in group_json.pokemon_species {
+        triples.push(Triple {
+            subject: group_id.into(),
+            predicate: NamedNode::new(format!("{POKE}hasPokemonSpecies"))?,
+            object: NamedNodeRef::new(species.url.as_str())?.into(),
+        });
     }
-    Ok(())
+
+    send_resource(sink, url, triples)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_egg_groups() {
-        assert!((egg_group_to_nt(
+        let mut graph = Graph::new();
+        egg_group_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("egg_groups", &graph);
     }
 }
@@ -1,28 +1,34 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
 use oxrdf::{BlankNode, BlankNodeRef, Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
 use rustemon::model::evolution::ChainLink;
-use rustemon::Follow;
-use std::error::Error;
+use rustemon::model::evolution::EvolutionChain;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
 use crate::create_type_triple;
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
+use crate::POKEAPI;
 use crate::POKEMONKG;
 use crate::SCHEMA;
 
 pub async fn evolution_chain_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let chains = match rustemon::evolution::evolution_chain::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let chains = match client
+        .get_all_unnamed_entries::<EvolutionChain>("evolution-chain")
+        .await
+    {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all evolution chains: {:?}", e);
-            return Err(e.into());
+            return failures.record("evolution-chain", e);
         }
     };
     let len = chains.len();
@@ -32,63 +38,86 @@ pub async fn evolution_chain_to_nt(
     for (index, p) in chains.into_iter().enumerate() {
         pb.set_message(format!("evolution chain {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples: Vec<Triple> = vec![];
-        let chain_id = NamedNodeRef::new(p.url.as_str())?;
-        let chain_json = match p.follow(&client).await {
-            Ok(list) => list,
-            Err(e) => {
-                println!("error getting evolution chain info for {}: {e}", &p.url);
-                return Err(e.into());
-            }
-        };
-        // Add rdf:type declaration
-        triples.push(create_type_triple(chain_id, "EvolutionChain")?);
+        failures.skip_failed(
+            "evolution-chain",
+            Collection::EvolutionChain
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+    Ok(())
+}
 
-        triples.push(Triple {
-            subject: chain_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(chain_json.id.to_string(), xsd::INTEGER).into(),
-        });
+/// Converts the evolution chain at `url`, e.g. `https://pokeapi.co/api/v2/evolution-chain/1/`.
+pub async fn evolution_chain_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let chain_id = NamedNodeRef::new(url)?;
+    let chain_json: EvolutionChain = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(chain_id, "EvolutionChain")?);
 
-        if let Some(trigger_item) = chain_json.baby_trigger_item {
-            triples.push(Triple {
-                subject: chain_id.into(),
-                predicate: NamedNode::new(format!("{POKE}triggerItem"))?,
-                object: NamedNode::new(trigger_item.url.as_str())?.into(),
-            });
-        }
+    triples.push(Triple {
+        subject: chain_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(chain_json.id.to_string(), xsd::INTEGER).into(),
+    });
 
-        // chain link
-        let link_id = BlankNode::new(format!("chain{}_base", chain_json.id))?;
+    if let Some(trigger_item) = chain_json.baby_trigger_item {
         triples.push(Triple {
             subject: chain_id.into(),
-            predicate: NamedNode::new(format!("{POKE}chain"))?,
-            object: link_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}triggerItem"))?,
+            object: NamedNode::new(trigger_item.url.as_str())?.into(),
         });
-        triples.extend_from_slice(&chain_link_to_nt(link_id.as_ref(), &chain_json.chain)?);
-
-        // TODO evolves_to
-        for (i, evolve) in chain_json.chain.evolves_to.into_iter().enumerate() {
-            let link_id = BlankNode::new(format!("chain{}_evolve{}", chain_json.id, i))?;
-            triples.push(Triple {
-                subject: chain_id.into(),
-                predicate: NamedNode::new(format!("{POKE}evolvesTo"))?,
-                object: link_id.as_ref().into(),
-            });
-            triples.extend_from_slice(&chain_link_to_nt(link_id.as_ref(), &evolve)?);
-        }
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
-        }
     }
-    Ok(())
+
+    // chain link
+    let link_id = BlankNode::new(format!("chain{}_base", chain_json.id))?;
+    triples.push(Triple {
+        subject: chain_id.into(),
+        predicate: NamedNode::new(format!("{POKE}chain"))?,
+        object: link_id.as_ref().into(),
+    });
+    triples.extend_from_slice(&chain_tree_to_nt(link_id.as_ref(), &chain_json.chain, 1)?);
+
+    send_resource(sink, url, triples)
 }
 
-pub fn chain_link_to_nt(
+// Walks the whole evolution tree below `link`. Every link records its stage (the base species
+// is stage 1) and points to the links it evolves into, and the species get a direct
+// evolvesInto edge so the tree can be followed without going through the link nodes.
+pub fn chain_tree_to_nt(
     link_id: BlankNodeRef,
     link: &ChainLink,
-) -> Result<Vec<Triple>, Box<dyn Error + Send + Sync>> {
+    stage: i64,
+) -> Result<Vec<Triple>, Error> {
+    let mut triples = chain_link_to_nt(link_id, link)?;
+    triples.push(Triple {
+        subject: link_id.into(),
+        predicate: NamedNode::new(format!("{POKE}stage"))?,
+        object: Literal::new_typed_literal(stage.to_string(), xsd::INTEGER).into(),
+    });
+    for (i, evolve) in link.evolves_to.iter().enumerate() {
+        let evolve_id = BlankNode::new(format!("{}_evolve{}", link_id.as_str(), i))?;
+        triples.push(Triple {
+            subject: link_id.into(),
+            predicate: NamedNode::new(format!("{POKE}evolvesTo"))?,
+            object: evolve_id.as_ref().into(),
+        });
+        triples.push(Triple {
+            subject: NamedNode::new(link.species.url.as_str())?.into(),
+            predicate: NamedNode::new(format!("{POKE}evolvesInto"))?,
+            object: NamedNode::new(evolve.species.url.as_str())?.into(),
+        });
+        triples.extend(chain_tree_to_nt(evolve_id.as_ref(), evolve, stage + 1)?);
+    }
+    Ok(triples)
+}
+
+pub fn chain_link_to_nt(link_id: BlankNodeRef, link: &ChainLink) -> Result<Vec<Triple>, Error> {
     let mut triples = vec![];
     triples.push(Triple {
         subject: link_id.into(),
@@ -101,11 +130,7 @@ pub fn chain_link_to_nt(
         object: NamedNode::new(link.species.url.as_str())?.into(),
     });
     for (i, detail) in link.evolution_details.clone().into_iter().enumerate() {
-        let detail_id = BlankNode::new(format!(
-            "link{}_evolutionDetail{}",
-            link_id.to_string().replace("_:", ""),
-            i
-        ))?;
+        let detail_id = BlankNode::new(format!("link{}_evolutionDetail{}", link_id.as_str(), i))?;
         triples.push(Triple {
             subject: link_id.into(),
             predicate: NamedNode::new(format!("{POKE}evolutionDetail"))?,
@@ -124,10 +149,11 @@ pub fn chain_link_to_nt(
             object: NamedNode::new(detail.trigger.url.as_str())?.into(),
         });
         if let Some(gender) = detail.gender {
+            // evolution details only carry the gender id, link to the gender resource instead
             triples.push(Triple {
                 subject: detail_id.as_ref().into(),
                 predicate: NamedNode::new(format!("{POKE}gender"))?,
-                object: Literal::new_typed_literal(gender.to_string(), xsd::INTEGER).into(),
+                object: NamedNode::new(format!("{POKEAPI}gender/{gender}/"))?.into(),
             });
         }
         if let Some(item) = &detail.held_item {
@@ -249,15 +275,20 @@ pub fn chain_link_to_nt(
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_evolution_chains() {
-        assert!((evolution_chain_to_nt(
+        let mut graph = Graph::new();
+        evolution_chain_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("evolutions_chains", &graph);
     }
 }
@@ -0,0 +1,120 @@
+use indicatif::{MultiProgress, ProgressBar};
+use oxrdf::vocab::xsd;
+use oxrdf::{BlankNode, Literal, NamedNode, NamedNodeRef, Triple};
+use rustemon::model::pokemon::Gender;
+use std::sync::Arc;
+
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::create_type_triple;
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
+use crate::POKE;
+use crate::SCHEMA;
+
+pub async fn gender_to_nt(
+    bar: &MultiProgress,
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_genders = match client.get_all_entries::<Gender>("gender").await {
+        Ok(list) => list,
+        Err(e) => {
+            return failures.record("gender", e);
+        }
+    };
+    let len = all_genders.len();
+    let pb =
+        bar.add(ProgressBar::new(len.try_into().unwrap()).with_style(crate::create_bar_style()));
+    pb.finish_with_message("done");
+    for (index, p) in all_genders.into_iter().enumerate() {
+        pb.set_message(format!("gender {}/{}", index + 1, len));
+        pb.inc(1);
+        failures.skip_failed(
+            "gender",
+            Collection::Gender
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Converts the gender at `url`, e.g. `https://pokeapi.co/api/v2/gender/1/`.
+pub async fn gender_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let gender_id = NamedNodeRef::new(url)?;
+    let gender_json: Gender = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(gender_id, "Gender")?);
+
+    triples.push(Triple {
+        subject: gender_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(gender_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: gender_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(gender_json.name).into(),
+    });
+
+    for (i, detail) in gender_json.pokemon_species_details.into_iter().enumerate() {
+        let detail_id = BlankNode::new(format!("gender{}_speciesdetail{}", gender_json.id, i))?;
+        triples.push(Triple {
+            subject: gender_id.into(),
+            predicate: NamedNode::new(format!("{POKE}speciesGenderRate"))?,
+            object: detail_id.as_ref().into(),
+        });
+        triples.push(Triple {
+            subject: detail_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}species"))?,
+            object: NamedNode::new(detail.pokemon_species.url)?.into(),
+        });
+        // chance of being female in eighths, -1 for genderless
+        triples.push(Triple {
+            subject: detail_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}genderRate"))?,
+            object: Literal::new_typed_literal(detail.rate.to_string(), xsd::INTEGER).into(),
+        });
+    }
+
+    for species in gender_json.required_for_evolution {
+        triples.push(Triple {
+            subject: gender_id.into(),
+            predicate: NamedNode::new(format!("{POKE}requiredForEvolution"))?,
+            object: NamedNode::new(species.url)?.into(),
+        });
+    }
+
+    send_resource(sink, url, triples)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
+
+    #[tokio::test]
+    async fn test_genders() {
+        let mut graph = Graph::new();
+        gender_to_nt(
+            &MultiProgress::new(),
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
+        )
+        .await
+        .unwrap();
+        fixtures::assert_golden("genders", &graph);
+    }
+}
@@ -1,26 +1,28 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
 use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use rustemon::model::games::Generation;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
-use crate::create_type_triple;
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
 use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
 
 pub async fn generation_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let all_generations = match rustemon::games::generation::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_generations = match client.get_all_entries::<Generation>("generation").await {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all generations: {:?}", e);
-            return Err(e.into());
+            return failures.record("generation", e);
         }
     };
     let len = all_generations.len();
@@ -30,107 +32,118 @@ pub async fn generation_to_nt(
     for (index, p) in all_generations.into_iter().enumerate() {
         pb.set_message(format!("generation {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples: Vec<Triple> = vec![];
-        let gen_id = NamedNodeRef::new(p.url.as_str())?;
-        let gen_json = match p.follow(&client).await {
-            Ok(list) => list,
-            Err(e) => {
-                println!("error getting generation info for {}: {e}", &p.url);
-                return Err(e.into());
-            }
-        };
-        // Add rdf:type declaration
-        triples.push(create_type_triple(gen_id, "Generation")?);
+        failures.skip_failed(
+            "generation",
+            Collection::Generation
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+
+    Ok(())
+}
 
+/// Converts the generation at `url`, e.g. `https://pokeapi.co/api/v2/generation/1/`.
+pub async fn generation_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let gen_id = NamedNodeRef::new(url)?;
+    let gen_json: Generation = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(gen_id, "Generation")?);
+
+    triples.push(Triple {
+        subject: gen_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(gen_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: gen_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(gen_json.name).into(),
+    });
+    // abilities
+    for a in gen_json.abilities {
         triples.push(Triple {
             subject: gen_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(gen_json.id.to_string(), xsd::INTEGER).into(),
+            predicate: NamedNode::new(format!("{POKE}hasNewAbiltiy"))?,
+            object: NamedNode::new(a.url)?.into(),
         });
+    }
+    // names
+    for n in gen_json.names {
+        // TODO only english for now
+        if n.language.name == "en" {
+            triples.extend(localized_to_nt(
+                gen_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                n.name,
+                &n.language,
+            )?);
+        }
+    }
+    // main_region
+    triples.push(Triple {
+        subject: gen_id.into(),
+        predicate: NamedNode::new(format!("{POKE}region"))?,
+        object: NamedNode::new(&gen_json.main_region.url)?.into(),
+    });
+    // moves
+    for m in gen_json.moves {
         triples.push(Triple {
             subject: gen_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(gen_json.name).into(),
+            predicate: NamedNode::new(format!("{POKE}hasNewMove"))?,
+            object: NamedNode::new(m.url)?.into(),
         });
-        // abilities
-        for a in gen_json.abilities {
-            triples.push(Triple {
-                subject: gen_id.into(),
-                predicate: NamedNode::new(format!("{POKE}hasNewAbiltiy"))?,
-                object: NamedNode::new(a.url)?.into(),
-            });
-        }
-        // names
-        for n in gen_json.names {
-            // TODO only english for now
-            if n.language.name == "en" {
-                triples.push(Triple {
-                    subject: gen_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}names"))?,
-                    object: Literal::new_simple_literal(n.name).into(),
-                });
-            }
-        }
-        // main_region
+    }
+    // pokemon_species
+    for s in gen_json.pokemon_species {
         triples.push(Triple {
             subject: gen_id.into(),
-            predicate: NamedNode::new(format!("{POKE}region"))?,
-            object: NamedNode::new(&gen_json.main_region.url)?.into(),
+            predicate: NamedNode::new(format!("{POKE}hasNewSpecies"))?,
+            object: NamedNode::new(s.url)?.into(),
         });
-        // moves
-        for m in gen_json.moves {
-            triples.push(Triple {
-                subject: gen_id.into(),
-                predicate: NamedNode::new(format!("{POKE}hasNewMove"))?,
-                object: NamedNode::new(m.url)?.into(),
-            });
-        }
-        // pokemon_species
-        for s in gen_json.pokemon_species {
-            triples.push(Triple {
-                subject: gen_id.into(),
-                predicate: NamedNode::new(format!("{POKE}hasNewSpecies"))?,
-                object: NamedNode::new(s.url)?.into(),
-            });
-        }
-        // types
-        for t in gen_json.types {
-            triples.push(Triple {
-                subject: gen_id.into(),
-                predicate: NamedNode::new(format!("{POKE}hasNewType"))?,
-                object: NamedNode::new(t.url)?.into(),
-            });
-        }
-        // version_groups
-        for v in gen_json.version_groups {
-            triples.push(Triple {
-                subject: gen_id.into(),
-                predicate: NamedNode::new(format!("{POKE}hasNewVersionGroup"))?,
-                object: NamedNode::new(v.url)?.into(),
-            });
-        }
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
-        }
     }
-
-    Ok(())
+    // types
+    for t in gen_json.types {
+        triples.push(Triple {
+            subject: gen_id.into(),
+            predicate: NamedNode::new(format!("{POKE}hasNewType"))?,
+            object: NamedNode::new(t.url)?.into(),
+        });
+    }
+    // version_groups
+    for v in gen_json.version_groups {
+        triples.push(Triple {
+            subject: gen_id.into(),
+            predicate: NamedNode::new(format!("{POKE}hasNewVersionGroup"))?,
+            object: NamedNode::new(v.url)?.into(),
+        });
+    }
+    send_resource(sink, url, triples)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_generations() {
-        assert!((generation_to_nt(
+        let mut graph = Graph::new();
+        generation_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("generations", &graph);
     }
 }
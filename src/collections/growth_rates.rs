@@ -1,26 +1,28 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
 use oxrdf::{BlankNode, Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use rustemon::model::pokemon::GrowthRate;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
-use crate::create_type_triple;
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
 use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
 
 pub async fn growth_rate_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let all_rates = match rustemon::pokemon::growth_rate::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_rates = match client.get_all_entries::<GrowthRate>("growth-rate").await {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all growth rates: {:?}", e);
-            return Err(e.into());
+            return failures.record("growth-rate", e);
         }
     };
     let len = all_rates.len();
@@ -30,92 +32,102 @@ pub async fn growth_rate_to_nt(
     for (index, p) in all_rates.into_iter().enumerate() {
         pb.set_message(format!("growth rate {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples: Vec<Triple> = vec![];
-        let growth_id = NamedNodeRef::new(p.url.as_str())?;
-        let growth_json = match p.follow(&client).await {
-            Ok(list) => list,
-            Err(e) => {
-                println!("error getting growth rate: {:?}", e);
-                return Err(e.into());
-            }
-        };
-        // Add rdf:type declaration
-        triples.push(create_type_triple(growth_id, "GrowthRate")?);
+        failures.skip_failed(
+            "growth-rate",
+            Collection::GrowthRate
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Converts the growth rate at `url`, e.g. `https://pokeapi.co/api/v2/growth-rate/1/`.
+pub async fn growth_rate_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let growth_id = NamedNodeRef::new(url)?;
+    let growth_json: GrowthRate = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(growth_id, "GrowthRate")?);
 
+    triples.push(Triple {
+        subject: growth_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(growth_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: growth_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(growth_json.name).into(),
+    });
+    triples.push(Triple {
+        subject: growth_id.into(),
+        predicate: NamedNode::new(format!("{POKE}formula"))?,
+        object: Literal::new_simple_literal(growth_json.formula).into(),
+    });
+    for d in growth_json.descriptions {
+        // TODO only English for now
+        if d.language.name == "en" {
+            triples.extend(localized_to_nt(
+                growth_id.into(),
+                NamedNode::new(format!("{SCHEMA}description"))?,
+                d.description,
+                &d.language,
+            )?);
+        }
+    }
+    for l in growth_json.levels {
+        let level_id = BlankNode::new(format!("growthrate{}_explevel{}", growth_json.id, l.level))?;
         triples.push(Triple {
             subject: growth_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(growth_json.id.to_string(), xsd::INTEGER).into(),
+            predicate: NamedNode::new(format!("{POKE}hasExpLevel"))?,
+            object: level_id.as_ref().into(),
         });
         triples.push(Triple {
-            subject: growth_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(growth_json.name).into(),
+            subject: level_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}level"))?,
+            object: Literal::new_typed_literal(l.level.to_string(), xsd::INTEGER).into(),
         });
+        triples.push(Triple {
+            subject: level_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}experience"))?,
+            object: Literal::new_typed_literal(l.experience.to_string(), xsd::INTEGER).into(),
+        });
+    }
+    // pokemon_species
+    for p in growth_json.pokemon_species {
         triples.push(Triple {
             subject: growth_id.into(),
-            predicate: NamedNode::new(format!("{POKE}formula"))?,
-            object: Literal::new_simple_literal(growth_json.formula).into(),
+            predicate: NamedNode::new(format!("{POKE}hasSpecies"))?,
+            object: NamedNode::new(p.url)?.into(),
         });
-        for d in growth_json.descriptions {
-            // TODO only English for now
-            if d.language.name == "en" {
-                triples.push(Triple {
-                    subject: growth_id.into(),
-                    predicate: NamedNode::new(format!("{SCHEMA}description"))?,
-                    object: Literal::new_simple_literal(d.description).into(),
-                });
-            }
-        }
-        for l in growth_json.levels {
-            let level_id =
-                BlankNode::new(format!("growthrate{}_explevel{}", growth_json.id, l.level))?;
-            triples.push(Triple {
-                subject: growth_id.into(),
-                predicate: NamedNode::new(format!("{POKE}hasExpLevel"))?,
-                object: level_id.as_ref().into(),
-            });
-            triples.push(Triple {
-                subject: level_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{POKE}level"))?,
-                object: Literal::new_typed_literal(l.level.to_string(), xsd::INTEGER).into(),
-            });
-            triples.push(Triple {
-                subject: level_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{POKE}experience"))?,
-                object: Literal::new_typed_literal(l.experience.to_string(), xsd::INTEGER).into(),
-            });
-        }
-        // pokemon_species
-        for p in growth_json.pokemon_species {
-            triples.push(Triple {
-                subject: growth_id.into(),
-                predicate: NamedNode::new(format!("{POKE}hasSpecies"))?,
-                object: NamedNode::new(p.url)?.into(),
-            });
-        }
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
-        }
     }
-
-    Ok(())
+    send_resource(sink, url, triples)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_growth_rates() {
-        assert!((growth_rate_to_nt(
+        let mut graph = Graph::new();
+        growth_rate_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("growth_rates", &graph);
     }
 }
@@ -1,27 +1,32 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
 use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use rustemon::model::pokemon::PokemonHabitat;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
-use crate::create_type_triple;
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
 use crate::POKEMONKG;
 use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
 
 pub async fn habitat_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let habitats = match rustemon::pokemon::pokemon_habitat::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let habitats = match client
+        .get_all_entries::<PokemonHabitat>("pokemon-habitat")
+        .await
+    {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all habitats: {:?}", e);
-            return Err(e.into());
+            return failures.record("pokemon-habitat", e);
         }
     };
     let len = habitats.len();
@@ -31,70 +36,81 @@ pub async fn habitat_to_nt(
     for (index, p) in habitats.into_iter().enumerate() {
         pb.set_message(format!("habitat {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples: Vec<Triple> = vec![];
-        let habitat_id = NamedNodeRef::new(p.url.as_str())?;
-        let habitat_json = match p.follow(&client).await {
-            Ok(list) => list,
-            Err(e) => {
-                println!("error getting habitat info for {}: {e}", &p.url);
-                return Err(e.into());
-            }
-        };
-        // Add rdf:type declaration
-        triples.push(create_type_triple(habitat_id, "Habitat")?);
+        failures.skip_failed(
+            "pokemon-habitat",
+            Collection::PokemonHabitat
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
 
-        triples.push(Triple {
-            subject: habitat_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(habitat_json.id.to_string(), xsd::INTEGER).into(),
-        });
-        triples.push(Triple {
-            subject: habitat_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(habitat_json.name).into(),
-        });
+    Ok(())
+}
 
-        for name in habitat_json.names {
-            // TODO only english for now
-            if name.language.name == "en" {
-                triples.push(Triple {
-                    subject: habitat_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}names"))?,
-                    object: Literal::new_simple_literal(name.name).into(),
-                });
-            }
-        }
+/// Converts the pokemon habitat at `url`, e.g. `https://pokeapi.co/api/v2/pokemon-habitat/1/`.
+pub async fn habitat_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let habitat_id = NamedNodeRef::new(url)?;
+    let habitat_json: PokemonHabitat = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(habitat_id, "Habitat")?);
 
-        for species in habitat_json.pokemon_species {
-            triples.push(Triple {
-                subject: habitat_id.into(),
-                predicate: NamedNode::new(format!("{POKEMONKG}hasPokemonSpecies"))?,
-                object: NamedNodeRef::new(species.url.as_str())?.into(),
-            });
-        }
+    triples.push(Triple {
+        subject: habitat_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(habitat_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: habitat_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(habitat_json.name).into(),
+    });
 
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
+    for name in habitat_json.names {
+        // TODO only english for now
+        if name.language.name == "en" {
+            triples.extend(localized_to_nt(
+                habitat_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                name.name,
+                &name.language,
+            )?);
         }
     }
 
-    Ok(())
+    for species in habitat_json.pokemon_species {
+        triples.push(Triple {
+            subject: habitat_id.into(),
+            predicate: NamedNode::new(format!("{POKEMONKG}hasPokemonSpecies"))?,
+            object: NamedNodeRef::new(species.url.as_str())?.into(),
+        });
+    }
+
+    send_resource(sink, url, triples)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_habitats() {
-        assert!((habitat_to_nt(
+        let mut graph = Graph::new();
+        habitat_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("habitats", &graph);
     }
 }
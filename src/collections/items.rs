@@ -1,26 +1,29 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
 use oxrdf::{BlankNode, Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use rustemon::model::items::Item;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
-use crate::create_type_triple;
+use crate::client::Client;
+use crate::collections::sprites::{sprite_to_nt, SpriteSource};
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
 use crate::SCHEMA;
+use crate::{create_localized_literal, create_type_triple, localized_to_nt};
 
 pub async fn item_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let all_items = match rustemon::items::item::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_items = match client.get_all_entries::<Item>("item").await {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all items: {:?}", e);
-            return Err(e.into());
+            return failures.record("item", e);
         }
     };
     let len = all_items.len();
@@ -30,210 +33,226 @@ pub async fn item_to_nt(
     for (index, p) in all_items.into_iter().enumerate() {
         pb.set_message(format!("items {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples: Vec<Triple> = vec![];
-        let item_id = NamedNodeRef::new(p.url.as_str())?;
-        let item_json = match p.follow(&client).await {
-            Ok(list) => list,
-            Err(e) => {
-                println!("error getting item info for {}: {e}", &p.url);
-                return Err(e.into());
-            }
-        };
-        // Add rdf:type declaration
-        triples.push(create_type_triple(item_id, "Item")?);
+        failures.skip_failed(
+            "item",
+            Collection::Item
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+    Ok(())
+}
+
+/// Converts the item at `url`, e.g. `https://pokeapi.co/api/v2/item/1/`.
+pub async fn item_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let item_id = NamedNodeRef::new(url)?;
+    let item_json: Item = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(item_id, "Item")?);
+
+    triples.push(Triple {
+        subject: item_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(item_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: item_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(item_json.name).into(),
+    });
 
+    triples.push(Triple {
+        subject: item_id.into(),
+        predicate: NamedNode::new(format!("{POKE}cost"))?,
+        object: Literal::new_typed_literal(item_json.cost.to_string(), xsd::INTEGER).into(),
+    });
+
+    if let Some(power) = item_json.fling_power {
         triples.push(Triple {
             subject: item_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(item_json.id.to_string(), xsd::INTEGER).into(),
+            predicate: NamedNode::new(format!("{POKE}flingPower"))?,
+            object: Literal::new_typed_literal(power.to_string(), xsd::INTEGER).into(),
         });
+    }
+    if let Some(effect) = item_json.fling_effect {
         triples.push(Triple {
             subject: item_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(item_json.name).into(),
+            predicate: NamedNode::new(format!("{POKE}flingEffect"))?,
+            object: NamedNode::new(effect.url)?.into(),
         });
+    }
 
+    for attribute in item_json.attributes {
         triples.push(Triple {
             subject: item_id.into(),
-            predicate: NamedNode::new(format!("{POKE}cost"))?,
-            object: Literal::new_typed_literal(item_json.cost.to_string(), xsd::INTEGER).into(),
+            predicate: NamedNode::new(format!("{POKE}hasAttribute"))?,
+            object: NamedNode::new(attribute.url)?.into(),
         });
+    }
 
-        if let Some(power) = item_json.fling_power {
-            triples.push(Triple {
-                subject: item_id.into(),
-                predicate: NamedNode::new(format!("{POKE}flingPower"))?,
-                object: Literal::new_typed_literal(power.to_string(), xsd::INTEGER).into(),
-            });
-        }
-        if let Some(effect) = item_json.fling_effect {
-            triples.push(Triple {
-                subject: item_id.into(),
-                predicate: NamedNode::new(format!("{POKE}flingEffect"))?,
-                object: NamedNode::new(effect.url)?.into(),
-            });
-        }
+    triples.push(Triple {
+        subject: item_id.into(),
+        predicate: NamedNode::new(format!("{POKE}itemCategory"))?,
+        object: NamedNode::new(item_json.category.url)?.into(),
+    });
 
-        for attribute in item_json.attributes {
+    for (i, effect) in item_json.effect_entries.into_iter().enumerate() {
+        // TODO only english for now
+        if effect.language.name == "en" {
+            let effect_id = BlankNode::new(format!("item{}_effect{}", item_json.id, i))?;
             triples.push(Triple {
                 subject: item_id.into(),
-                predicate: NamedNode::new(format!("{POKE}hasAttribute"))?,
-                object: NamedNode::new(attribute.url)?.into(),
+                predicate: NamedNode::new(format!("{POKE}hasEffect"))?,
+                object: effect_id.as_ref().into(),
             });
-        }
-
-        triples.push(Triple {
-            subject: item_id.into(),
-            predicate: NamedNode::new(format!("{POKE}itemCategory"))?,
-            object: NamedNode::new(item_json.category.url)?.into(),
-        });
-
-        for (i, effect) in item_json.effect_entries.into_iter().enumerate() {
-            // TODO only english for now
-            if effect.language.name == "en" {
-                let effect_id = BlankNode::new(format!("item{}_effect{}", item_json.id, i))?;
-                triples.push(Triple {
-                    subject: item_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}hasEffect"))?,
-                    object: effect_id.as_ref().into(),
-                });
-                triples.push(Triple {
-                    subject: effect_id.as_ref().into(),
-                    predicate: NamedNode::new(format!("{SCHEMA}description"))?,
-                    object: Literal::new_simple_literal(effect.effect).into(),
-                });
-                triples.push(Triple {
-                    subject: effect_id.as_ref().into(),
-                    predicate: NamedNode::new(format!("{POKE}shortEffect"))?,
-                    object: Literal::new_simple_literal(effect.short_effect).into(),
-                });
-            }
-        }
-
-        for flavor_text in item_json.flavor_text_entries {
-            // TODO only english for now
-            if flavor_text.language.name == "en" {
-                triples.push(Triple {
-                    subject: item_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}hasFlavorText"))?,
-                    object: Literal::new_simple_literal(flavor_text.text).into(),
-                });
-            }
-        }
-
-        // TODO game_indices
-        for (i, index) in item_json.game_indices.into_iter().enumerate() {
-            let gi_id = BlankNode::new(format!("item{}_gameindex{}", item_json.id, i))?;
             triples.push(Triple {
-                subject: item_id.into(),
-                predicate: NamedNode::new(format!("{POKE}gameIndex"))?,
-                object: gi_id.as_ref().into(),
+                subject: effect_id.as_ref().into(),
+                predicate: NamedNode::new(format!("{SCHEMA}description"))?,
+                object: create_localized_literal(effect.effect, &effect.language.name)?.into(),
             });
             triples.push(Triple {
-                subject: gi_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{POKE}index"))?,
-                object: Literal::new_typed_literal(index.game_index.to_string(), xsd::INTEGER)
+                subject: effect_id.as_ref().into(),
+                predicate: NamedNode::new(format!("{POKE}shortEffect"))?,
+                object: create_localized_literal(effect.short_effect, &effect.language.name)?
                     .into(),
             });
             triples.push(Triple {
-                subject: gi_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{POKE}generation"))?,
-                object: NamedNode::new(index.generation.url)?.into(),
+                subject: effect_id.as_ref().into(),
+                predicate: NamedNode::new(format!("{SCHEMA}inLanguage"))?,
+                object: NamedNode::new(effect.language.url)?.into(),
             });
         }
+    }
 
-        for name in item_json.names {
-            // TODO only english for now
-            if name.language.name == "en" {
-                triples.push(Triple {
-                    subject: item_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}names"))?,
-                    object: Literal::new_simple_literal(name.name).into(),
-                });
-            }
+    for flavor_text in item_json.flavor_text_entries {
+        // TODO only english for now
+        if flavor_text.language.name == "en" {
+            triples.extend(localized_to_nt(
+                item_id.into(),
+                NamedNode::new(format!("{POKE}hasFlavorText"))?,
+                flavor_text.text,
+                &flavor_text.language,
+            )?);
         }
+    }
 
-        // TODO sprites
-        if let Some(sprite) = item_json.sprites.default {
-            // rustemon defines sprite as Option<String>, PokeAPI has image URL:
-            // example: https://pokeapi.co/api/v2/item/1
-            triples.push(Triple {
-                subject: item_id.into(),
-                predicate: NamedNode::new(format!("{POKE}defaultSprite"))?,
-                object: NamedNode::new(sprite)?.into(),
-            });
+    // TODO game_indices
+    for (i, index) in item_json.game_indices.into_iter().enumerate() {
+        let gi_id = BlankNode::new(format!("item{}_gameindex{}", item_json.id, i))?;
+        triples.push(Triple {
+            subject: item_id.into(),
+            predicate: NamedNode::new(format!("{POKE}gameIndex"))?,
+            object: gi_id.as_ref().into(),
+        });
+        triples.push(Triple {
+            subject: gi_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}index"))?,
+            object: Literal::new_typed_literal(index.game_index.to_string(), xsd::INTEGER).into(),
+        });
+        triples.push(Triple {
+            subject: gi_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}generation"))?,
+            object: NamedNode::new(index.generation.url)?.into(),
+        });
+    }
+
+    for name in item_json.names {
+        // TODO only english for now
+        if name.language.name == "en" {
+            triples.extend(localized_to_nt(
+                item_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                name.name,
+                &name.language,
+            )?);
         }
+    }
 
-        for (i, poke) in item_json.held_by_pokemon.into_iter().enumerate() {
-            let hold_id = BlankNode::new(format!("item{}_heldbypokemon{}", item_json.id, i))?;
-            triples.push(Triple {
-                subject: item_id.into(),
-                predicate: NamedNode::new(format!("{POKE}heldByPokemon"))?,
-                object: hold_id.as_ref().into(),
-            });
+    // sprites
+    if let Some(url) = item_json.sprites.default {
+        let sprite_id = BlankNode::new(format!("item{}_sprite0", item_json.id))?;
+        let sprite = (SpriteSource::Default, "default", url);
+        triples.append(&mut sprite_to_nt(item_id, sprite_id.as_ref(), &sprite)?);
+    }
+
+    for (i, poke) in item_json.held_by_pokemon.into_iter().enumerate() {
+        let hold_id = BlankNode::new(format!("item{}_heldbypokemon{}", item_json.id, i))?;
+        triples.push(Triple {
+            subject: item_id.into(),
+            predicate: NamedNode::new(format!("{POKE}heldByPokemon"))?,
+            object: hold_id.as_ref().into(),
+        });
+        triples.push(Triple {
+            subject: hold_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}pokemon"))?,
+            object: NamedNode::new(poke.pokemon.url)?.into(),
+        });
+        for (j, version_detail) in poke.version_details.into_iter().enumerate() {
+            let version_detail_id = BlankNode::new(format!(
+                "item{}_heldbypokemon{}_versiondetail{}",
+                item_json.id, i, j
+            ))?;
             triples.push(Triple {
                 subject: hold_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{POKE}pokemon"))?,
-                object: NamedNode::new(poke.pokemon.url)?.into(),
+                predicate: NamedNode::new(format!("{POKE}versionDetail"))?,
+                object: version_detail_id.as_ref().into(),
             });
-            for (j, version_detail) in poke.version_details.into_iter().enumerate() {
-                let version_detail_id = BlankNode::new(format!(
-                    "item{}_heldbypokemon{}_versiondetail{}",
-                    item_json.id, i, j
-                ))?;
-                triples.push(Triple {
-                    subject: hold_id.as_ref().into(),
-                    predicate: NamedNode::new(format!("{POKE}versionDetail"))?,
-                    object: version_detail_id.as_ref().into(),
-                });
-                triples.push(Triple {
-                    subject: version_detail_id.as_ref().into(),
-                    predicate: NamedNode::new(format!("{POKE}rarity"))?,
-                    object: Literal::new_typed_literal(
-                        version_detail.rarity.to_string(),
-                        xsd::INTEGER,
-                    )
+            triples.push(Triple {
+                subject: version_detail_id.as_ref().into(),
+                predicate: NamedNode::new(format!("{POKE}rarity"))?,
+                object: Literal::new_typed_literal(version_detail.rarity.to_string(), xsd::INTEGER)
                     .into(),
-                });
-                triples.push(Triple {
-                    subject: version_detail_id.as_ref().into(),
-                    predicate: NamedNode::new(format!("{POKE}version"))?,
-                    object: NamedNode::new(version_detail.version.url)?.into(),
-                });
-            }
-        }
-
-        if let Some(baby_trigger) = item_json.baby_trigger_for {
+            });
             triples.push(Triple {
-                subject: item_id.into(),
-                predicate: NamedNode::new(format!("{POKE}babyTriggerFor"))?,
-                object: NamedNode::new(baby_trigger.url)?.into(),
+                subject: version_detail_id.as_ref().into(),
+                predicate: NamedNode::new(format!("{POKE}version"))?,
+                object: NamedNode::new(version_detail.version.url)?.into(),
             });
         }
+    }
 
-        // TODO machines
+    if let Some(baby_trigger) = item_json.baby_trigger_for {
+        triples.push(Triple {
+            subject: item_id.into(),
+            predicate: NamedNode::new(format!("{POKE}babyTriggerFor"))?,
+            object: NamedNode::new(baby_trigger.url)?.into(),
+        });
+    }
 
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
-        }
+    for machine in item_json.machines {
+        triples.push(Triple {
+            subject: item_id.into(),
+            predicate: NamedNode::new(format!("{POKE}machine"))?,
+            object: NamedNode::new(machine.machine.url)?.into(),
+        });
     }
-    Ok(())
+
+    send_resource(sink, url, triples)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_items() {
-        assert!((item_to_nt(
+        let mut graph = Graph::new();
+        item_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("items", &graph);
     }
 }
@@ -0,0 +1,120 @@
+use indicatif::{MultiProgress, ProgressBar};
+use oxrdf::vocab::xsd;
+use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
+use rustemon::model::utility::Language;
+use std::sync::Arc;
+
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
+use crate::POKE;
+use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
+
+pub async fn language_to_nt(
+    bar: &MultiProgress,
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_languages = match client.get_all_entries::<Language>("language").await {
+        Ok(list) => list,
+        Err(e) => {
+            return failures.record("language", e);
+        }
+    };
+    let len = all_languages.len();
+    let pb =
+        bar.add(ProgressBar::new(len.try_into().unwrap()).with_style(crate::create_bar_style()));
+    pb.finish_with_message("done");
+    for (index, p) in all_languages.into_iter().enumerate() {
+        pb.set_message(format!("language {}/{}", index + 1, len));
+        pb.inc(1);
+        failures.skip_failed(
+            "language",
+            Collection::Language
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Converts the language at `url`, e.g. `https://pokeapi.co/api/v2/language/1/`.
+pub async fn language_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let language_id = NamedNodeRef::new(url)?;
+    let language_json: Language = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(language_id, "Language")?);
+
+    triples.push(Triple {
+        subject: language_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(language_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: language_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(language_json.name).into(),
+    });
+
+    triples.push(Triple {
+        subject: language_id.into(),
+        predicate: NamedNode::new(format!("{POKE}official"))?,
+        object: Literal::new_typed_literal(language_json.official.to_string(), xsd::BOOLEAN).into(),
+    });
+    triples.push(Triple {
+        subject: language_id.into(),
+        predicate: NamedNode::new(format!("{POKE}iso639"))?,
+        object: Literal::new_simple_literal(language_json.iso639).into(),
+    });
+    triples.push(Triple {
+        subject: language_id.into(),
+        predicate: NamedNode::new(format!("{POKE}iso3166"))?,
+        object: Literal::new_simple_literal(language_json.iso3166).into(),
+    });
+
+    for name in language_json.names {
+        // TODO only english for now
+        if name.language.name == "en" {
+            triples.extend(localized_to_nt(
+                language_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                name.name,
+                &name.language,
+            )?);
+        }
+    }
+
+    send_resource(sink, url, triples)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
+
+    #[tokio::test]
+    async fn test_languages() {
+        let mut graph = Graph::new();
+        language_to_nt(
+            &MultiProgress::new(),
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
+        )
+        .await
+        .unwrap();
+        fixtures::assert_golden("languages", &graph);
+    }
+}
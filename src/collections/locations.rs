@@ -1,26 +1,28 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
 use oxrdf::{BlankNode, Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use rustemon::model::locations::Location;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
-use crate::create_type_triple;
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
 use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
 
 pub async fn location_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let all_locations = match rustemon::locations::location::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_locations = match client.get_all_entries::<Location>("location").await {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all locations: {:?}", e);
-            return Err(e.into());
+            return failures.record("location", e);
         }
     };
     let len = all_locations.len();
@@ -30,93 +32,104 @@ pub async fn location_to_nt(
     for (index, p) in all_locations.into_iter().enumerate() {
         pb.set_message(format!("location {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples: Vec<Triple> = vec![];
-        let location_id = NamedNodeRef::new(p.url.as_str())?;
-        let location_json = match p.follow(&client).await {
-            Ok(list) => list,
-            Err(e) => {
-                println!("error getting location info for {}: {e}", &p.url);
-                return Err(e.into());
-            }
-        };
-        // Add rdf:type declaration
-        triples.push(create_type_triple(location_id, "Location")?);
+        failures.skip_failed(
+            "location",
+            Collection::Location
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Converts the location at `url`, e.g. `https://pokeapi.co/api/v2/location/1/`.
+pub async fn location_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let location_id = NamedNodeRef::new(url)?;
+    let location_json: Location = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(location_id, "Location")?);
 
+    triples.push(Triple {
+        subject: location_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(location_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: location_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(location_json.name).into(),
+    });
+    if let Some(region) = location_json.region {
         triples.push(Triple {
             subject: location_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(location_json.id.to_string(), xsd::INTEGER).into(),
+            predicate: NamedNode::new(format!("{POKE}region"))?,
+            object: NamedNode::new(region.url)?.into(),
         });
+    }
+    for n in location_json.names {
+        // TODO only english for now
+        if n.language.name == "en" {
+            triples.extend(localized_to_nt(
+                location_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                n.name,
+                &n.language,
+            )?);
+        }
+    }
+    for (i, gi) in location_json.game_indices.into_iter().enumerate() {
+        let gi_id = BlankNode::new(format!("location{}_gameindex{}", location_json.id, i))?;
+        triples.push(Triple {
+            subject: location_id.into(),
+            predicate: NamedNode::new(format!("{POKE}gameIndex"))?,
+            object: gi_id.as_ref().into(),
+        });
+        triples.push(Triple {
+            subject: gi_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}index"))?,
+            object: Literal::new_typed_literal(gi.game_index.to_string(), xsd::INTEGER).into(),
+        });
+        triples.push(Triple {
+            subject: gi_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}generation"))?,
+            object: NamedNode::new(gi.generation.url)?.into(),
+        });
+    }
+    for a in location_json.areas {
         triples.push(Triple {
             subject: location_id.into(),
             predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(location_json.name).into(),
+            object: NamedNode::new(a.url)?.into(),
         });
-        if let Some(region) = location_json.region {
-            triples.push(Triple {
-                subject: location_id.into(),
-                predicate: NamedNode::new(format!("{POKE}region"))?,
-                object: NamedNode::new(region.url)?.into(),
-            });
-        }
-        for n in location_json.names {
-            // TODO only english for now
-            if n.language.name == "en" {
-                triples.push(Triple {
-                    subject: location_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}names"))?,
-                    object: Literal::new_simple_literal(n.name).into(),
-                });
-            }
-        }
-        for (i, gi) in location_json.game_indices.into_iter().enumerate() {
-            let gi_id = BlankNode::new(format!("location{}_gameindex{}", location_json.id, i))?;
-            triples.push(Triple {
-                subject: location_id.into(),
-                predicate: NamedNode::new(format!("{POKE}gameIndex"))?,
-                object: gi_id.as_ref().into(),
-            });
-            triples.push(Triple {
-                subject: gi_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{POKE}index"))?,
-                object: Literal::new_typed_literal(gi.game_index.to_string(), xsd::INTEGER).into(),
-            });
-            triples.push(Triple {
-                subject: gi_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{POKE}generation"))?,
-                object: NamedNode::new(gi.generation.url)?.into(),
-            });
-        }
-        for a in location_json.areas {
-            triples.push(Triple {
-                subject: location_id.into(),
-                predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-                object: NamedNode::new(a.url)?.into(),
-            });
-            // TODO location_area_to_nt
-        }
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
-        }
+        // TODO location_area_to_nt
     }
-
-    Ok(())
+    send_resource(sink, url, triples)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_locations() {
-        assert!((location_to_nt(
+        let mut graph = Graph::new();
+        location_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("locations", &graph);
     }
 }
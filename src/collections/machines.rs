@@ -0,0 +1,112 @@
+use indicatif::{MultiProgress, ProgressBar};
+use oxrdf::vocab::xsd;
+use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
+use rustemon::model::machines::Machine;
+use std::sync::Arc;
+
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::create_type_triple;
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
+use crate::POKE;
+use crate::SCHEMA;
+
+pub async fn machine_to_nt(
+    bar: &MultiProgress,
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_machines = match client.get_all_unnamed_entries::<Machine>("machine").await {
+        Ok(list) => list,
+        Err(e) => {
+            return failures.record("machine", e);
+        }
+    };
+    let len = all_machines.len();
+    let pb =
+        bar.add(ProgressBar::new(len.try_into().unwrap()).with_style(crate::create_bar_style()));
+    pb.finish_with_message("done");
+    for (index, p) in all_machines.into_iter().enumerate() {
+        pb.set_message(format!("machine {}/{}", index + 1, len));
+        pb.inc(1);
+        failures.skip_failed(
+            "machine",
+            Collection::Machine
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Converts the machine at `url`, e.g. `https://pokeapi.co/api/v2/machine/1/`.
+pub async fn machine_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let machine_id = NamedNodeRef::new(url)?;
+    let machine_json: Machine = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(machine_id, "Machine")?);
+
+    triples.push(Triple {
+        subject: machine_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(machine_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    // machines are unnamed resources, the TM/HM item name is the closest thing to a label
+    triples.push(Triple {
+        subject: machine_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(&machine_json.item.name).into(),
+    });
+
+    // item
+    triples.push(Triple {
+        subject: machine_id.into(),
+        predicate: NamedNode::new(format!("{POKE}item"))?,
+        object: NamedNode::new(machine_json.item.url)?.into(),
+    });
+    // move
+    triples.push(Triple {
+        subject: machine_id.into(),
+        predicate: NamedNode::new(format!("{POKE}teachesMove"))?,
+        object: NamedNode::new(machine_json.move_.url)?.into(),
+    });
+    // version_group
+    triples.push(Triple {
+        subject: machine_id.into(),
+        predicate: NamedNode::new(format!("{POKE}versionGroup"))?,
+        object: NamedNode::new(machine_json.version_group.url)?.into(),
+    });
+
+    send_resource(sink, url, triples)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
+
+    #[tokio::test]
+    async fn test_machines() {
+        let mut graph = Graph::new();
+        machine_to_nt(
+            &MultiProgress::new(),
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
+        )
+        .await
+        .unwrap();
+        fixtures::assert_golden("machines", &graph);
+    }
+}
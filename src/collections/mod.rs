@@ -1,25 +1,503 @@
-pub(crate) mod abilities;
-pub(crate) mod berries;
-pub(crate) mod berry_firmness;
-pub(crate) mod berry_flavors;
-pub(crate) mod damage_class;
-pub(crate) mod egg_groups;
-pub(crate) mod evolutions_chains;
-pub(crate) mod forms;
-pub(crate) mod generations;
-pub(crate) mod growth_rates;
-pub(crate) mod habitats;
-pub(crate) mod items;
-pub(crate) mod locations;
-pub(crate) mod move_target;
-pub(crate) mod moves;
-pub(crate) mod natures;
-pub(crate) mod pal_park;
-pub(crate) mod poke_types;
-pub(crate) mod pokedex;
-pub(crate) mod pokemon;
-pub(crate) mod regions;
-pub(crate) mod shapes;
-pub(crate) mod species;
-pub(crate) mod stats;
-pub(crate) mod triggers;
+pub mod abilities;
+pub mod berries;
+pub mod berry_firmness;
+pub mod berry_flavors;
+pub mod characteristics;
+pub mod colors;
+pub mod damage_class;
+pub mod egg_groups;
+pub mod evolutions_chains;
+pub mod forms;
+pub mod genders;
+pub mod generations;
+pub mod growth_rates;
+pub mod habitats;
+pub mod items;
+pub mod languages;
+pub mod locations;
+pub mod machines;
+pub mod move_ailment;
+pub mod move_battle_style;
+pub mod move_category;
+pub mod move_learn_method;
+pub mod move_target;
+pub mod moves;
+pub mod natures;
+pub mod pal_park;
+pub mod poke_types;
+pub mod pokeathlon_stats;
+pub mod pokedex;
+pub mod pokemon;
+pub mod regions;
+pub mod shapes;
+pub mod species;
+pub(crate) mod sprites;
+pub mod stats;
+pub mod triggers;
+
+use indicatif::MultiProgress;
+use oxrdf::Triple;
+use serde_json::Value;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::client::Client;
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
+use crate::POKEAPI;
+
+/// A PokeAPI collection that is converted to RDF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Collection {
+    Berry,
+    BerryFirmness,
+    BerryFlavor,
+    EvolutionChain,
+    EvolutionTrigger,
+    Generation,
+    Pokedex,
+    Item,
+    Location,
+    PalParkArea,
+    Region,
+    Machine,
+    Move,
+    MoveAilment,
+    MoveBattleStyle,
+    MoveCategory,
+    MoveDamageClass,
+    MoveLearnMethod,
+    MoveTarget,
+    Ability,
+    Characteristic,
+    EggGroup,
+    Gender,
+    GrowthRate,
+    Nature,
+    PokeathlonStat,
+    Pokemon,
+    PokemonColor,
+    PokemonForm,
+    PokemonHabitat,
+    PokemonShape,
+    PokemonSpecies,
+    Stat,
+    Type,
+    Language,
+}
+
+impl Collection {
+    /// Every collection, in the order a full build converts them.
+    pub const ALL: [Collection; 35] = [
+        // berry endpoints
+        Collection::Berry,
+        Collection::BerryFirmness,
+        Collection::BerryFlavor,
+        // contests endpoints
+        // TODO contest type
+        // TODO contest effect
+        // TODO super contest effect
+        // encounters endpoints
+        // TODO encounter method
+        // TODO encounter condition
+        // TODO encounter condition value
+        // evolution endpoints
+        Collection::EvolutionChain,
+        Collection::EvolutionTrigger,
+        // games endpoints
+        Collection::Generation,
+        Collection::Pokedex,
+        // TODO version
+        // TODO version group
+        // items endpoints
+        Collection::Item,
+        // TODO item attribute
+        // TODO item category
+        // TODO item fling effect
+        // TODO item pocket
+        // locations endpoints
+        Collection::Location,
+        // TODO location area
+        Collection::PalParkArea,
+        Collection::Region,
+        // machines endpoints
+        Collection::Machine,
+        // moves endpoints
+        Collection::Move,
+        Collection::MoveAilment,
+        Collection::MoveBattleStyle,
+        Collection::MoveCategory,
+        Collection::MoveDamageClass,
+        Collection::MoveLearnMethod,
+        Collection::MoveTarget,
+        // pokemon endpoints
+        Collection::Ability,
+        Collection::Characteristic,
+        Collection::EggGroup,
+        Collection::Gender,
+        Collection::GrowthRate,
+        Collection::Nature,
+        Collection::PokeathlonStat,
+        Collection::Pokemon,
+        Collection::PokemonColor,
+        Collection::PokemonForm,
+        Collection::PokemonHabitat,
+        Collection::PokemonShape,
+        Collection::PokemonSpecies,
+        Collection::Stat,
+        Collection::Type,
+        // utility endpoints
+        Collection::Language,
+    ];
+
+    /// The PokeAPI endpoint, e.g. `pokemon-species`.
+    pub fn endpoint(self) -> &'static str {
+        match self {
+            Collection::Berry => "berry",
+            Collection::BerryFirmness => "berry-firmness",
+            Collection::BerryFlavor => "berry-flavor",
+            Collection::EvolutionChain => "evolution-chain",
+            Collection::EvolutionTrigger => "evolution-trigger",
+            Collection::Generation => "generation",
+            Collection::Pokedex => "pokedex",
+            Collection::Item => "item",
+            Collection::Location => "location",
+            Collection::PalParkArea => "pal-park-area",
+            Collection::Region => "region",
+            Collection::Machine => "machine",
+            Collection::Move => "move",
+            Collection::MoveAilment => "move-ailment",
+            Collection::MoveBattleStyle => "move-battle-style",
+            Collection::MoveCategory => "move-category",
+            Collection::MoveDamageClass => "move-damage-class",
+            Collection::MoveLearnMethod => "move-learn-method",
+            Collection::MoveTarget => "move-target",
+            Collection::Ability => "ability",
+            Collection::Characteristic => "characteristic",
+            Collection::EggGroup => "egg-group",
+            Collection::Gender => "gender",
+            Collection::GrowthRate => "growth-rate",
+            Collection::Nature => "nature",
+            Collection::PokeathlonStat => "pokeathlon-stat",
+            Collection::Pokemon => "pokemon",
+            Collection::PokemonColor => "pokemon-color",
+            Collection::PokemonForm => "pokemon-form",
+            Collection::PokemonHabitat => "pokemon-habitat",
+            Collection::PokemonShape => "pokemon-shape",
+            Collection::PokemonSpecies => "pokemon-species",
+            Collection::Stat => "stat",
+            Collection::Type => "type",
+            Collection::Language => "language",
+        }
+    }
+
+    /// Converts every resource of the collection into `sink`.
+    pub async fn convert(
+        self,
+        bar: &MultiProgress,
+        client: Arc<Client>,
+        sink: &mut dyn TripleSink,
+        failures: &Failures,
+    ) -> Result<(), Error> {
+        match self {
+            Collection::Berry => berries::berry_to_nt(bar, client, sink, failures).await,
+            Collection::BerryFirmness => {
+                berry_firmness::firmness_to_nt(bar, client, sink, failures).await
+            }
+            Collection::BerryFlavor => {
+                berry_flavors::flavors_to_nt(bar, client, sink, failures).await
+            }
+            Collection::EvolutionChain => {
+                evolutions_chains::evolution_chain_to_nt(bar, client, sink, failures).await
+            }
+            Collection::EvolutionTrigger => {
+                triggers::trigger_to_nt(bar, client, sink, failures).await
+            }
+            Collection::Generation => {
+                generations::generation_to_nt(bar, client, sink, failures).await
+            }
+            Collection::Pokedex => pokedex::pokedex_to_nt(bar, client, sink, failures).await,
+            Collection::Item => items::item_to_nt(bar, client, sink, failures).await,
+            Collection::Location => locations::location_to_nt(bar, client, sink, failures).await,
+            Collection::PalParkArea => {
+                pal_park::pal_park_area_to_nt(bar, client, sink, failures).await
+            }
+            Collection::Region => regions::region_to_nt(bar, client, sink, failures).await,
+            Collection::Machine => machines::machine_to_nt(bar, client, sink, failures).await,
+            Collection::Move => moves::move_to_nt(bar, client, sink, failures).await,
+            Collection::MoveAilment => {
+                move_ailment::move_ailment_to_nt(bar, client, sink, failures).await
+            }
+            Collection::MoveBattleStyle => {
+                move_battle_style::move_battle_style_to_nt(bar, client, sink, failures).await
+            }
+            Collection::MoveCategory => {
+                move_category::move_category_to_nt(bar, client, sink, failures).await
+            }
+            Collection::MoveDamageClass => {
+                damage_class::damage_class_to_nt(bar, client, sink, failures).await
+            }
+            Collection::MoveLearnMethod => {
+                move_learn_method::move_learn_method_to_nt(bar, client, sink, failures).await
+            }
+            Collection::MoveTarget => {
+                move_target::move_target_to_nt(bar, client, sink, failures).await
+            }
+            Collection::Ability => abilities::ability_to_nt(bar, client, sink, failures).await,
+            Collection::Characteristic => {
+                characteristics::characteristic_to_nt(bar, client, sink, failures).await
+            }
+            Collection::EggGroup => egg_groups::egg_group_to_nt(bar, client, sink, failures).await,
+            Collection::Gender => genders::gender_to_nt(bar, client, sink, failures).await,
+            Collection::GrowthRate => {
+                growth_rates::growth_rate_to_nt(bar, client, sink, failures).await
+            }
+            Collection::Nature => natures::nature_to_nt(bar, client, sink, failures).await,
+            Collection::PokeathlonStat => {
+                pokeathlon_stats::pokeathlon_stat_to_nt(bar, client, sink, failures).await
+            }
+            Collection::Pokemon => pokemon::pokemon_to_nt(bar, client, sink, failures).await,
+            Collection::PokemonColor => colors::color_to_nt(bar, client, sink, failures).await,
+            Collection::PokemonForm => forms::form_to_nt(bar, client, sink, failures).await,
+            Collection::PokemonHabitat => {
+                habitats::habitat_to_nt(bar, client, sink, failures).await
+            }
+            Collection::PokemonShape => shapes::shape_to_nt(bar, client, sink, failures).await,
+            Collection::PokemonSpecies => species::species_to_nt(bar, client, sink, failures).await,
+            Collection::Stat => stats::stat_to_nt(bar, client, sink, failures).await,
+            Collection::Type => poke_types::type_to_nt(bar, client, sink, failures).await,
+            Collection::Language => languages::language_to_nt(bar, client, sink, failures).await,
+        }
+    }
+
+    /// Converts the resource at `url` alone, e.g. `https://pokeapi.co/api/v2/pokemon/25/`. Types
+    /// leave out the effectiveness matrices, which need every type. When the sink kept the
+    /// triples of the same JSON from an earlier build (see [`TripleSink::reuse`]) those are
+    /// sent instead.
+    pub async fn convert_resource(
+        self,
+        client: &Client,
+        url: &str,
+        sink: &mut dyn TripleSink,
+    ) -> Result<(), Error> {
+        let (json_hash, fetched) = client.json_hash(url, self.links()).await?;
+        if let Some(triples) = sink.reuse(url, &json_hash)? {
+            client.forget(&fetched);
+            return send_resource(sink, url, triples);
+        }
+        let converted = self.convert_json(client, url, sink).await;
+        client.forget(&fetched);
+        converted
+    }
+
+    // Fields of the JSON that link to further resources the converter fetches, like a Pokémon's
+    // encounters. Their JSON is part of the hash so a change there is not hidden by reuse.
+    fn links(self) -> &'static [&'static str] {
+        match self {
+            Collection::Pokemon => &["location_area_encounters"],
+            _ => &[],
+        }
+    }
+
+    // Runs the converter, which takes the JSON that was fetched for the hash
+    async fn convert_json(
+        self,
+        client: &Client,
+        url: &str,
+        sink: &mut dyn TripleSink,
+    ) -> Result<(), Error> {
+        match self {
+            Collection::Berry => berries::berry_resource_to_nt(client, url, sink).await,
+            Collection::BerryFirmness => {
+                berry_firmness::firmness_resource_to_nt(client, url, sink).await
+            }
+            Collection::BerryFlavor => {
+                berry_flavors::flavor_resource_to_nt(client, url, sink).await
+            }
+            Collection::EvolutionChain => {
+                evolutions_chains::evolution_chain_resource_to_nt(client, url, sink).await
+            }
+            Collection::EvolutionTrigger => {
+                triggers::trigger_resource_to_nt(client, url, sink).await
+            }
+            Collection::Generation => {
+                generations::generation_resource_to_nt(client, url, sink).await
+            }
+            Collection::Pokedex => pokedex::pokedex_resource_to_nt(client, url, sink).await,
+            Collection::Item => items::item_resource_to_nt(client, url, sink).await,
+            Collection::Location => locations::location_resource_to_nt(client, url, sink).await,
+            Collection::PalParkArea => {
+                pal_park::pal_park_area_resource_to_nt(client, url, sink).await
+            }
+            Collection::Region => regions::region_resource_to_nt(client, url, sink).await,
+            Collection::Machine => machines::machine_resource_to_nt(client, url, sink).await,
+            Collection::Move => moves::move_resource_to_nt(client, url, sink).await,
+            Collection::MoveAilment => {
+                move_ailment::move_ailment_resource_to_nt(client, url, sink).await
+            }
+            Collection::MoveBattleStyle => {
+                move_battle_style::move_battle_style_resource_to_nt(client, url, sink).await
+            }
+            Collection::MoveCategory => {
+                move_category::move_category_resource_to_nt(client, url, sink).await
+            }
+            Collection::MoveDamageClass => {
+                damage_class::damage_class_resource_to_nt(client, url, sink).await
+            }
+            Collection::MoveLearnMethod => {
+                move_learn_method::move_learn_method_resource_to_nt(client, url, sink).await
+            }
+            Collection::MoveTarget => {
+                move_target::move_target_resource_to_nt(client, url, sink).await
+            }
+            Collection::Ability => abilities::ability_resource_to_nt(client, url, sink).await,
+            Collection::Characteristic => {
+                characteristics::characteristic_resource_to_nt(client, url, sink).await
+            }
+            Collection::EggGroup => egg_groups::egg_group_resource_to_nt(client, url, sink).await,
+            Collection::Gender => genders::gender_resource_to_nt(client, url, sink).await,
+            Collection::GrowthRate => {
+                growth_rates::growth_rate_resource_to_nt(client, url, sink).await
+            }
+            Collection::Nature => natures::nature_resource_to_nt(client, url, sink).await,
+            Collection::PokeathlonStat => {
+                pokeathlon_stats::pokeathlon_stat_resource_to_nt(client, url, sink).await
+            }
+            Collection::Pokemon => pokemon::pokemon_resource_to_nt(client, url, sink).await,
+            Collection::PokemonColor => colors::color_resource_to_nt(client, url, sink).await,
+            Collection::PokemonForm => forms::form_resource_to_nt(client, url, sink).await,
+            Collection::PokemonHabitat => habitats::habitat_resource_to_nt(client, url, sink).await,
+            Collection::PokemonShape => shapes::shape_resource_to_nt(client, url, sink).await,
+            Collection::PokemonSpecies => species::species_resource_to_nt(client, url, sink).await,
+            Collection::Stat => stats::stat_resource_to_nt(client, url, sink).await,
+            Collection::Type => poke_types::type_resource_to_nt(client, url, sink).await,
+            Collection::Language => languages::language_resource_to_nt(client, url, sink).await,
+        }
+    }
+
+    /// The collection of an endpoint, e.g. `pokemon-species`.
+    pub fn from_endpoint(endpoint: &str) -> Option<Collection> {
+        Collection::ALL
+            .into_iter()
+            .find(|c| c.endpoint() == endpoint)
+    }
+
+    /// The collection of a PokeAPI resource URL, e.g. `https://pokeapi.co/api/v2/type/10/`. None
+    /// for lists, other sites and endpoints that are not converted.
+    pub fn of_url(url: &str) -> Option<Collection> {
+        let path = url.strip_prefix(POKEAPI)?.strip_suffix('/')?;
+        match path.split('/').collect::<Vec<_>>()[..] {
+            [endpoint, id] if id.parse::<i64>().is_ok() => Collection::from_endpoint(endpoint),
+            _ => None,
+        }
+    }
+
+    /// The URL of a resource by id, or by name for collections whose resources have names. Names
+    /// are looked up in the endpoint's list.
+    pub async fn resource_url(self, client: &Client, id_or_name: &str) -> Result<String, Error> {
+        let endpoint = self.endpoint();
+        if id_or_name.parse::<i64>().is_ok() {
+            return Ok(format!("{POKEAPI}{endpoint}/{id_or_name}/"));
+        }
+        if matches!(
+            self,
+            Collection::Characteristic | Collection::EvolutionChain | Collection::Machine
+        ) {
+            return Err(Error::InvalidData(format!(
+                "{endpoint} resources have no name, use the id instead of {id_or_name}"
+            )));
+        }
+        client
+            .get_all_entries::<Value>(endpoint)
+            .await?
+            .into_iter()
+            .find(|entry| entry.name == id_or_name)
+            .map(|entry| entry.url)
+            .ok_or_else(|| Error::InvalidData(format!("no {endpoint} named {id_or_name}")))
+    }
+}
+
+impl FromStr for Collection {
+    type Err = Error;
+
+    /// Parses an endpoint, e.g. `pokemon-species`.
+    fn from_str(endpoint: &str) -> Result<Self, Self::Err> {
+        Collection::from_endpoint(endpoint)
+            .ok_or_else(|| Error::InvalidData(format!("{endpoint} is not a converted endpoint")))
+    }
+}
+
+// Hands the triples built from one resource to the sink, announced by its URL
+pub(crate) fn send_resource(
+    sink: &mut dyn TripleSink,
+    url: &str,
+    triples: Vec<Triple>,
+) -> Result<(), Error> {
+    sink.start_resource(url)?;
+    for t in triples {
+        sink.triple(t)?;
+    }
+    Ok(())
+}
+
+// Converter tests run against the recorded PokeAPI responses in tests/fixtures/pokeapi and
+// compare the canonicalized triples with tests/fixtures/golden/<name>.nt. Run them with
+// UPDATE_GOLDEN=1 to rewrite the golden files after an intended modelling change.
+#[cfg(test)]
+pub(crate) mod fixtures {
+
+    use oxrdf::Graph;
+    use oxttl::NTriplesParser;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use crate::client::Client;
+    use crate::diff::canonicalize;
+
+    fn dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+    }
+
+    // The recorded PokeAPI responses
+    pub(crate) fn corpus() -> PathBuf {
+        dir().join("pokeapi")
+    }
+
+    pub(crate) fn client() -> Arc<Client> {
+        Arc::new(Client::with_fixtures(corpus()))
+    }
+
+    // Compares N-Triples, e.g. a whole dump, with `golden/{name}.nt`
+    pub(crate) fn assert_golden_nt(name: &str, nt: &str) {
+        let mut graph = Graph::new();
+        for t in NTriplesParser::new().for_slice(nt) {
+            graph.insert(&t.unwrap());
+        }
+        assert_golden(name, &graph);
+    }
+
+    pub(crate) fn assert_golden(name: &str, graph: &Graph) {
+        let mut lines: Vec<String> = canonicalize(graph)
+            .unwrap()
+            .iter()
+            .map(|t| format!("{t} ."))
+            .collect();
+        lines.sort();
+        let actual = lines.iter().map(|l| format!("{l}\n")).collect::<String>();
+
+        let path = dir().join("golden").join(format!("{name}.nt"));
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, &actual).unwrap();
+        }
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("no golden file {}", path.display()));
+        assert!(
+            actual == expected,
+            "triples differ from {}, rerun with UPDATE_GOLDEN=1 and review the diff\n{}",
+            path.display(),
+            actual
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use indicatif::{MultiProgress, ProgressBar};
+use oxrdf::vocab::xsd;
+use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
+use rustemon::model::moves::MoveAilment;
+use std::sync::Arc;
+
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
+use crate::POKE;
+use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
+
+pub async fn move_ailment_to_nt(
+    bar: &MultiProgress,
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_ailments = match client.get_all_entries::<MoveAilment>("move-ailment").await {
+        Ok(list) => list,
+        Err(e) => {
+            return failures.record("move-ailment", e);
+        }
+    };
+    let len = all_ailments.len();
+    let pb =
+        bar.add(ProgressBar::new(len.try_into().unwrap()).with_style(crate::create_bar_style()));
+    pb.finish_with_message("done");
+    for (index, p) in all_ailments.into_iter().enumerate() {
+        pb.set_message(format!("move ailment {}/{}", index + 1, len));
+        pb.inc(1);
+        failures.skip_failed(
+            "move-ailment",
+            Collection::MoveAilment
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Converts the move ailment at `url`, e.g. `https://pokeapi.co/api/v2/move-ailment/1/`.
+pub async fn move_ailment_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let ailment_id = NamedNodeRef::new(url)?;
+    let ailment_json: MoveAilment = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(ailment_id, "MoveAilment")?);
+
+    triples.push(Triple {
+        subject: ailment_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(ailment_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: ailment_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(ailment_json.name).into(),
+    });
+
+    for m in ailment_json.moves {
+        triples.push(Triple {
+            subject: ailment_id.into(),
+            predicate: NamedNode::new(format!("{POKE}move"))?,
+            object: NamedNode::new(m.url)?.into(),
+        });
+    }
+
+    for name in ailment_json.names {
+        // TODO only english for now
+        if name.language.name == "en" {
+            triples.extend(localized_to_nt(
+                ailment_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                name.name,
+                &name.language,
+            )?);
+        }
+    }
+
+    send_resource(sink, url, triples)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
+
+    #[tokio::test]
+    async fn test_move_ailments() {
+        let mut graph = Graph::new();
+        move_ailment_to_nt(
+            &MultiProgress::new(),
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
+        )
+        .await
+        .unwrap();
+        fixtures::assert_golden("move_ailment", &graph);
+    }
+}
@@ -0,0 +1,107 @@
+use indicatif::{MultiProgress, ProgressBar};
+use oxrdf::vocab::xsd;
+use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
+use rustemon::model::moves::MoveBattleStyle;
+use std::sync::Arc;
+
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
+use crate::POKE;
+use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
+
+pub async fn move_battle_style_to_nt(
+    bar: &MultiProgress,
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_styles = match client
+        .get_all_entries::<MoveBattleStyle>("move-battle-style")
+        .await
+    {
+        Ok(list) => list,
+        Err(e) => {
+            return failures.record("move-battle-style", e);
+        }
+    };
+    let len = all_styles.len();
+    let pb =
+        bar.add(ProgressBar::new(len.try_into().unwrap()).with_style(crate::create_bar_style()));
+    pb.finish_with_message("done");
+    for (index, p) in all_styles.into_iter().enumerate() {
+        pb.set_message(format!("move battle style {}/{}", index + 1, len));
+        pb.inc(1);
+        failures.skip_failed(
+            "move-battle-style",
+            Collection::MoveBattleStyle
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Converts the move battle style at `url`, e.g. `https://pokeapi.co/api/v2/move-battle-style/1/`.
+pub async fn move_battle_style_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let style_id = NamedNodeRef::new(url)?;
+    let style_json: MoveBattleStyle = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(style_id, "MoveBattleStyle")?);
+
+    triples.push(Triple {
+        subject: style_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(style_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: style_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(style_json.name).into(),
+    });
+
+    for name in style_json.names {
+        // TODO only english for now
+        if name.language.name == "en" {
+            triples.extend(localized_to_nt(
+                style_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                name.name,
+                &name.language,
+            )?);
+        }
+    }
+
+    send_resource(sink, url, triples)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
+
+    #[tokio::test]
+    async fn test_move_battle_styles() {
+        let mut graph = Graph::new();
+        move_battle_style_to_nt(
+            &MultiProgress::new(),
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
+        )
+        .await
+        .unwrap();
+        fixtures::assert_golden("move_battle_style", &graph);
+    }
+}
@@ -0,0 +1,115 @@
+use indicatif::{MultiProgress, ProgressBar};
+use oxrdf::vocab::xsd;
+use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
+use rustemon::model::moves::MoveCategory;
+use std::sync::Arc;
+
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
+use crate::POKE;
+use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
+
+pub async fn move_category_to_nt(
+    bar: &MultiProgress,
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_categories = match client
+        .get_all_entries::<MoveCategory>("move-category")
+        .await
+    {
+        Ok(list) => list,
+        Err(e) => {
+            return failures.record("move-category", e);
+        }
+    };
+    let len = all_categories.len();
+    let pb =
+        bar.add(ProgressBar::new(len.try_into().unwrap()).with_style(crate::create_bar_style()));
+    pb.finish_with_message("done");
+    for (index, p) in all_categories.into_iter().enumerate() {
+        pb.set_message(format!("move category {}/{}", index + 1, len));
+        pb.inc(1);
+        failures.skip_failed(
+            "move-category",
+            Collection::MoveCategory
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Converts the move category at `url`, e.g. `https://pokeapi.co/api/v2/move-category/1/`.
+pub async fn move_category_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let category_id = NamedNodeRef::new(url)?;
+    let category_json: MoveCategory = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(category_id, "MoveCategory")?);
+
+    triples.push(Triple {
+        subject: category_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(category_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: category_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(category_json.name).into(),
+    });
+
+    for d in category_json.descriptions {
+        // TODO only english for now
+        if d.language.name == "en" {
+            triples.extend(localized_to_nt(
+                category_id.into(),
+                NamedNode::new(format!("{SCHEMA}description"))?,
+                d.description,
+                &d.language,
+            )?);
+        }
+    }
+
+    for m in category_json.moves {
+        triples.push(Triple {
+            subject: category_id.into(),
+            predicate: NamedNode::new(format!("{POKE}move"))?,
+            object: NamedNode::new(m.url)?.into(),
+        });
+    }
+
+    send_resource(sink, url, triples)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
+
+    #[tokio::test]
+    async fn test_move_categories() {
+        let mut graph = Graph::new();
+        move_category_to_nt(
+            &MultiProgress::new(),
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
+        )
+        .await
+        .unwrap();
+        fixtures::assert_golden("move_category", &graph);
+    }
+}
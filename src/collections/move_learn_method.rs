@@ -0,0 +1,127 @@
+use indicatif::{MultiProgress, ProgressBar};
+use oxrdf::vocab::xsd;
+use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
+use rustemon::model::moves::MoveLearnMethod;
+use std::sync::Arc;
+
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
+use crate::POKE;
+use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
+
+pub async fn move_learn_method_to_nt(
+    bar: &MultiProgress,
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_methods = match client
+        .get_all_entries::<MoveLearnMethod>("move-learn-method")
+        .await
+    {
+        Ok(list) => list,
+        Err(e) => {
+            return failures.record("move-learn-method", e);
+        }
+    };
+    let len = all_methods.len();
+    let pb =
+        bar.add(ProgressBar::new(len.try_into().unwrap()).with_style(crate::create_bar_style()));
+    pb.finish_with_message("done");
+    for (index, p) in all_methods.into_iter().enumerate() {
+        pb.set_message(format!("move learn method {}/{}", index + 1, len));
+        pb.inc(1);
+        failures.skip_failed(
+            "move-learn-method",
+            Collection::MoveLearnMethod
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Converts the move learn method at `url`, e.g. `https://pokeapi.co/api/v2/move-learn-method/1/`.
+pub async fn move_learn_method_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let method_id = NamedNodeRef::new(url)?;
+    let method_json: MoveLearnMethod = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(method_id, "MoveLearnMethod")?);
+
+    triples.push(Triple {
+        subject: method_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(method_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: method_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(method_json.name).into(),
+    });
+
+    for d in method_json.descriptions {
+        // TODO only english for now
+        if d.language.name == "en" {
+            triples.extend(localized_to_nt(
+                method_id.into(),
+                NamedNode::new(format!("{SCHEMA}description"))?,
+                d.description,
+                &d.language,
+            )?);
+        }
+    }
+
+    for name in method_json.names {
+        // TODO only english for now
+        if name.language.name == "en" {
+            triples.extend(localized_to_nt(
+                method_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                name.name,
+                &name.language,
+            )?);
+        }
+    }
+
+    for vg in method_json.version_groups {
+        triples.push(Triple {
+            subject: method_id.into(),
+            predicate: NamedNode::new(format!("{POKE}versionGroup"))?,
+            object: NamedNode::new(vg.url)?.into(),
+        });
+    }
+
+    send_resource(sink, url, triples)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
+
+    #[tokio::test]
+    async fn test_move_learn_methods() {
+        let mut graph = Graph::new();
+        move_learn_method_to_nt(
+            &MultiProgress::new(),
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
+        )
+        .await
+        .unwrap();
+        fixtures::assert_golden("move_learn_method", &graph);
+    }
+}
@@ -1,25 +1,27 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use rustemon::model::moves::MoveTarget;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
-use crate::create_type_triple;
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
 use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
 
 pub async fn move_target_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let all_targets = match rustemon::moves::move_target::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_targets = match client.get_all_entries::<MoveTarget>("move-target").await {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all move targets: {:?}", e);
-            return Err(e.into());
+            return failures.record("move-target", e);
         }
     };
     let len = all_targets.len();
@@ -29,72 +31,84 @@ pub async fn move_target_to_nt(
     for (index, p) in all_targets.into_iter().enumerate() {
         pb.set_message(format!("move target {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples: Vec<Triple> = vec![];
-        let target_id = NamedNodeRef::new(p.url.as_str())?;
-        let target_json = match p.follow(&client).await {
-            Ok(list) => list,
-            Err(e) => {
-                println!("error getting move target info for {}: {e}", &p.url);
-                return Err(e.into());
-            }
-        };
-        // Add rdf:type declaration
-        triples.push(create_type_triple(target_id, "MoveTarget")?);
+        failures.skip_failed(
+            "move-target",
+            Collection::MoveTarget
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+    Ok(())
+}
+
+/// Converts the move target at `url`, e.g. `https://pokeapi.co/api/v2/move-target/1/`.
+pub async fn move_target_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let target_id = NamedNodeRef::new(url)?;
+    let target_json: MoveTarget = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(target_id, "MoveTarget")?);
 
+    triples.push(Triple {
+        subject: target_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(target_json.name.clone()).into(),
+    });
+    for d in target_json.descriptions.clone() {
+        if d.language.name == "en" {
+            triples.extend(localized_to_nt(
+                target_id.into(),
+                NamedNode::new(format!("{SCHEMA}description"))?,
+                d.description,
+                &d.language,
+            )?);
+        }
+    }
+    for m in target_json.moves {
         triples.push(Triple {
             subject: target_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(p.name.clone()).into(),
+            predicate: NamedNode::new(format!("{POKE}move"))?,
+            object: NamedNode::new(m.url)?.into(),
         });
-        for d in target_json.descriptions.clone() {
-            if d.language.name == "en" {
-                triples.push(Triple {
-                    subject: target_id.into(),
-                    predicate: NamedNode::new(format!("{SCHEMA}description"))?,
-                    object: Literal::new_simple_literal(d.description).into(),
-                });
-            }
-        }
-        for m in target_json.moves {
-            triples.push(Triple {
-                subject: target_id.into(),
-                predicate: NamedNode::new(format!("{POKE}move"))?,
-                object: NamedNode::new(m.url)?.into(),
-            });
-        }
-        // names
-        for d in target_json.names.clone() {
-            // TODO only english for now
-            if d.language.name == "en" {
-                triples.push(Triple {
-                    subject: target_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}names"))?,
-                    object: Literal::new_simple_literal(d.name).into(),
-                });
-            }
-        }
-
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
+    }
+    // names
+    for d in target_json.names.clone() {
+        // TODO only english for now
+        if d.language.name == "en" {
+            triples.extend(localized_to_nt(
+                target_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                d.name,
+                &d.language,
+            )?);
         }
     }
-    Ok(())
+
+    send_resource(sink, url, triples)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_move_targets() {
-        assert!((move_target_to_nt(
+        let mut graph = Graph::new();
+        move_target_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("move_target", &graph);
     }
 }
@@ -1,27 +1,29 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
 use oxrdf::{BlankNode, Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use rustemon::model::moves::Move;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
-use crate::create_type_triple;
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
 use crate::POKEMONKG;
 use crate::SCHEMA;
+use crate::{create_localized_literal, create_type_triple, localized_to_nt};
 
 pub async fn move_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let all_moves = match rustemon::moves::move_::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_moves = match client.get_all_entries::<Move>("move").await {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all moves: {:?}", e);
-            return Err(e.into());
+            return failures.record("move", e);
         }
     };
     let len = all_moves.len();
@@ -31,276 +33,347 @@ pub async fn move_to_nt(
     for (index, m) in all_moves.into_iter().enumerate() {
         pb.set_message(format!("move {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples = vec![];
-        let move_id = NamedNodeRef::new(&m.url)?;
-        // Add rdf:type declaration
-        triples.push(create_type_triple(move_id, "Move")?);
+        failures.skip_failed(
+            "move",
+            Collection::Move
+                .convert_resource(&client, &m.url, sink)
+                .await,
+        )?;
+    }
+    Ok(())
+}
+
+/// Converts the move at `url`, e.g. `https://pokeapi.co/api/v2/move/1/`.
+pub async fn move_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples = vec![];
+    let move_id = NamedNodeRef::new(url)?;
+    let move_json: Move = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(move_id, "Move")?);
 
+    triples.push(Triple {
+        subject: move_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(move_json.name.clone()).into(),
+    });
+    triples.push(Triple {
+        subject: move_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(move_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    if let Some(accuracy) = move_json.accuracy {
         triples.push(Triple {
             subject: move_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(m.name.clone()).into(),
+            predicate: NamedNode::new(format!("{POKEMONKG}accuracy"))?,
+            object: Literal::new_typed_literal(accuracy.to_string(), xsd::INTEGER).into(),
         });
-        let move_json = match m.follow(&client).await {
-            Ok(v) => v,
-            Err(e) => {
-                eprintln!("Error getting move info for {}: {e}", &m.url);
-                return Err(e.into());
-            }
-        };
+    }
+    if let Some(effect_chance) = move_json.effect_chance {
+        triples.push(Triple {
+            subject: move_id.into(),
+            predicate: NamedNode::new(format!("{POKE}effectChance"))?,
+            object: Literal::new_typed_literal(effect_chance.to_string(), xsd::INTEGER).into(),
+        });
+    }
+    if let Some(pp) = move_json.pp {
+        triples.push(Triple {
+            subject: move_id.into(),
+            predicate: NamedNode::new(format!("{POKEMONKG}basePowerPoints"))?,
+            object: Literal::new_typed_literal(pp.to_string(), xsd::INTEGER).into(),
+        });
+    }
+    triples.push(Triple {
+        subject: move_id.into(),
+        predicate: NamedNode::new(format!("{POKE}priority"))?,
+        object: Literal::new_typed_literal(move_json.priority.to_string(), xsd::INTEGER).into(),
+    });
+    if let Some(power) = move_json.power {
         triples.push(Triple {
             subject: move_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(move_json.id.to_string(), xsd::INTEGER).into(),
+            predicate: NamedNode::new(format!("{POKEMONKG}basePower"))?,
+            object: Literal::new_typed_literal(power.to_string(), xsd::INTEGER).into(),
         });
-        if move_json.accuracy.is_some() {
+    }
+    // TODO contest_combos
+    // TODO contest_type
+    // TODO contest_effect
+    // damage_class
+    triples.push(Triple {
+        subject: move_id.into(),
+        predicate: NamedNode::new(format!("{POKE}damageClass"))?,
+        object: NamedNode::new(&move_json.damage_class.url)?.into(),
+    });
+
+    for effect in move_json.effect_entries.clone() {
+        // TODO only english for now
+        if effect.language.name == "en" {
+            triples.extend(localized_to_nt(
+                move_id.into(),
+                NamedNode::new(format!("{POKEMONKG}effectDescription"))?,
+                effect.effect,
+                &effect.language,
+            )?);
+            triples.extend(localized_to_nt(
+                move_id.into(),
+                NamedNode::new(format!("{POKEMONKG}effectDescription"))?,
+                effect.short_effect,
+                &effect.language,
+            )?);
+        }
+    }
+    for (i, effect) in move_json.flavor_text_entries.into_iter().enumerate() {
+        // TODO only english for now
+        if effect.language.name == "en" {
+            let flavor_id = BlankNode::new(format!("move{}_flavortext{}", move_json.id, i))?;
             triples.push(Triple {
                 subject: move_id.into(),
-                predicate: NamedNode::new(format!("{POKEMONKG}accuracy"))?,
-                object: Literal::new_typed_literal(
-                    move_json.accuracy.unwrap().to_string(),
-                    xsd::INTEGER,
-                )
-                .into(),
+                predicate: NamedNode::new(format!("{POKE}flavorText"))?,
+                object: flavor_id.as_ref().into(),
             });
-        }
-        if move_json.effect_chance.is_some() {
             triples.push(Triple {
-                subject: move_id.into(),
-                predicate: NamedNode::new(format!("{POKE}effectChance"))?,
-                object: Literal::new_typed_literal(
-                    move_json.effect_chance.unwrap().to_string(),
-                    xsd::INTEGER,
-                )
-                .into(),
+                subject: flavor_id.as_ref().into(),
+                predicate: NamedNode::new(format!("{POKE}text"))?,
+                object: create_localized_literal(effect.flavor_text, &effect.language.name)?.into(),
             });
-        }
-        if move_json.pp.is_some() {
             triples.push(Triple {
-                subject: move_id.into(),
-                predicate: NamedNode::new(format!("{POKEMONKG}basePowerPoints"))?,
-                object: Literal::new_typed_literal(move_json.pp.unwrap().to_string(), xsd::INTEGER)
-                    .into(),
+                subject: flavor_id.as_ref().into(),
+                predicate: NamedNode::new(format!("{POKE}versionGroup"))?,
+                object: NamedNode::new(effect.version_group.url)?.into(),
             });
-        }
-        triples.push(Triple {
-            subject: move_id.into(),
-            predicate: NamedNode::new(format!("{POKE}priority"))?,
-            object: Literal::new_typed_literal(move_json.priority.to_string(), xsd::INTEGER).into(),
-        });
-        if move_json.power.is_some() {
             triples.push(Triple {
-                subject: move_id.into(),
-                predicate: NamedNode::new(format!("{POKEMONKG}basePower"))?,
-                object: Literal::new_typed_literal(
-                    move_json.power.unwrap().to_string(),
-                    xsd::INTEGER,
-                )
-                .into(),
+                subject: flavor_id.as_ref().into(),
+                predicate: NamedNode::new(format!("{SCHEMA}inLanguage"))?,
+                object: NamedNode::new(effect.language.url)?.into(),
             });
         }
-        // TODO contest_combos
-        // TODO contest_type
-        // TODO contest_effect
-        // damage_class
+    }
+    // learned_by_pokemon
+    for p in move_json.learned_by_pokemon {
         triples.push(Triple {
             subject: move_id.into(),
-            predicate: NamedNode::new(format!("{POKE}damageClass"))?,
-            object: NamedNode::new(&move_json.damage_class.url)?.into(),
+            predicate: NamedNode::new(format!("{POKE}learnedBy"))?,
+            object: NamedNode::new(p.url)?.into(),
         });
+    }
+    // generation
+    triples.push(Triple {
+        subject: move_id.into(),
+        predicate: NamedNode::new(format!("{POKE}generation"))?,
+        object: NamedNode::new(&move_json.generation.url)?.into(),
+    });
 
-        for effect in move_json.effect_entries.clone() {
-            // TODO only english for now
-            if effect.language.name == "en" {
-                triples.push(Triple {
-                    subject: move_id.into(),
-                    predicate: NamedNode::new(format!("{POKEMONKG}effectDescription"))?,
-                    object: Literal::new_simple_literal(effect.effect).into(),
-                });
-                triples.push(Triple {
-                    subject: move_id.into(),
-                    predicate: NamedNode::new(format!("{POKEMONKG}effectDescription"))?,
-                    object: Literal::new_simple_literal(effect.short_effect).into(),
-                });
-            }
-        }
-        for (i, effect) in move_json.flavor_text_entries.into_iter().enumerate() {
-            // TODO only english for now
-            if effect.language.name == "en" {
-                let flavor_id = BlankNode::new(format!("move{}_flavortext{}", move_json.id, i))?;
-                triples.push(Triple {
-                    subject: move_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}flavorText"))?,
-                    object: flavor_id.as_ref().into(),
-                });
-                triples.push(Triple {
-                    subject: flavor_id.as_ref().into(),
-                    predicate: NamedNode::new(format!("{POKE}text"))?,
-                    object: Literal::new_simple_literal(effect.flavor_text).into(),
-                });
-                triples.push(Triple {
-                    subject: flavor_id.as_ref().into(),
-                    predicate: NamedNode::new(format!("{POKE}versionGroup"))?,
-                    object: NamedNode::new(effect.version_group.url)?.into(),
-                });
-            }
-        }
-        // learned_by_pokemon
-        for p in move_json.learned_by_pokemon {
-            triples.push(Triple {
-                subject: move_id.into(),
-                predicate: NamedNode::new(format!("{POKE}learnedBy"))?,
-                object: NamedNode::new(p.url)?.into(),
-            });
-        }
-        // generation
+    // machines, the version group scoping lives on the machine node itself
+    for machine in move_json.machines {
         triples.push(Triple {
             subject: move_id.into(),
-            predicate: NamedNode::new(format!("{POKE}generation"))?,
-            object: NamedNode::new(&move_json.generation.url)?.into(),
+            predicate: NamedNode::new(format!("{POKE}taughtByMachine"))?,
+            object: NamedNode::new(machine.machine.url)?.into(),
         });
+    }
 
-        // TODO machines: this is going to be generation specific, skip until generation is implemented everywhere
-        if let Some(meta) = move_json.meta {
-            // TODO anything else important in MoveAilment?
-            triples.push(Triple {
-                subject: move_id.into(),
-                predicate: NamedNode::new(format!("{POKE}ailment"))?,
-                object: Literal::new_simple_literal(meta.ailment.name).into(),
-            });
-            // TODO anything else important in MoveCategory
-            triples.push(Triple {
-                subject: move_id.into(),
-                predicate: NamedNode::new(format!("{POKE}category"))?,
-                object: Literal::new_simple_literal(meta.category.name).into(),
-            });
+    if let Some(meta) = move_json.meta {
+        triples.push(Triple {
+            subject: move_id.into(),
+            predicate: NamedNode::new(format!("{POKE}ailment"))?,
+            object: NamedNode::new(meta.ailment.url)?.into(),
+        });
+        triples.push(Triple {
+            subject: move_id.into(),
+            predicate: NamedNode::new(format!("{POKE}category"))?,
+            object: NamedNode::new(meta.category.url)?.into(),
+        });
 
+        triples.push(Triple {
+            subject: move_id.into(),
+            predicate: NamedNode::new(format!("{POKE}ailmentChance"))?,
+            object: Literal::new_typed_literal(meta.ailment_chance.to_string(), xsd::INTEGER)
+                .into(),
+        });
+        triples.push(Triple {
+            subject: move_id.into(),
+            predicate: NamedNode::new(format!("{POKE}drain"))?,
+            object: Literal::new_typed_literal(meta.drain.to_string(), xsd::INTEGER).into(),
+        });
+        triples.push(Triple {
+            subject: move_id.into(),
+            predicate: NamedNode::new(format!("{POKE}healing"))?,
+            object: Literal::new_typed_literal(meta.healing.to_string(), xsd::INTEGER).into(),
+        });
+        triples.push(Triple {
+            subject: move_id.into(),
+            predicate: NamedNode::new(format!("{POKE}critRate"))?,
+            object: Literal::new_typed_literal(meta.crit_rate.to_string(), xsd::INTEGER).into(),
+        });
+        triples.push(Triple {
+            subject: move_id.into(),
+            predicate: NamedNode::new(format!("{POKE}flinchChance"))?,
+            object: Literal::new_simple_literal(meta.flinch_chance.to_string()).into(),
+        });
+        triples.push(Triple {
+            subject: move_id.into(),
+            predicate: NamedNode::new(format!("{POKE}statChance"))?,
+            object: Literal::new_typed_literal(meta.stat_chance.to_string(), xsd::INTEGER).into(),
+        });
+        if let Some(hits) = meta.min_hits {
             triples.push(Triple {
                 subject: move_id.into(),
-                predicate: NamedNode::new(format!("{POKE}ailmentChance"))?,
-                object: Literal::new_typed_literal(meta.ailment_chance.to_string(), xsd::INTEGER)
-                    .into(),
+                predicate: NamedNode::new(format!("{POKE}minHits"))?,
+                object: Literal::new_typed_literal(hits.to_string(), xsd::INTEGER).into(),
             });
+        }
+        if let Some(hits) = meta.max_hits {
             triples.push(Triple {
                 subject: move_id.into(),
-                predicate: NamedNode::new(format!("{POKE}drain"))?,
-                object: Literal::new_typed_literal(meta.drain.to_string(), xsd::INTEGER).into(),
+                predicate: NamedNode::new(format!("{POKE}maxHits"))?,
+                object: Literal::new_typed_literal(hits.to_string(), xsd::INTEGER).into(),
             });
+        }
+        if let Some(turns) = meta.min_turns {
             triples.push(Triple {
                 subject: move_id.into(),
-                predicate: NamedNode::new(format!("{POKE}healing"))?,
-                object: Literal::new_typed_literal(meta.healing.to_string(), xsd::INTEGER).into(),
+                predicate: NamedNode::new(format!("{POKE}minTurns"))?,
+                object: Literal::new_typed_literal(turns.to_string(), xsd::INTEGER).into(),
             });
+        }
+        if let Some(turns) = meta.max_turns {
             triples.push(Triple {
                 subject: move_id.into(),
-                predicate: NamedNode::new(format!("{POKE}critRate"))?,
-                object: Literal::new_typed_literal(meta.crit_rate.to_string(), xsd::INTEGER).into(),
+                predicate: NamedNode::new(format!("{POKE}maxTurns"))?,
+                object: Literal::new_typed_literal(turns.to_string(), xsd::INTEGER).into(),
             });
+        }
+    }
+    // names
+    for n in move_json.names {
+        if n.language.name == "en" {
+            triples.extend(localized_to_nt(
+                move_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                n.name,
+                &n.language,
+            )?);
+        }
+    }
+    // past_values, the values the move had before they were changed in the given version
+    // group. The current values are the ones directly on the move.
+    for (i, past) in move_json.past_values.into_iter().enumerate() {
+        let past_id = BlankNode::new(format!("move{}_pastvalues{}", move_json.id, i))?;
+        triples.push(Triple {
+            subject: move_id.into(),
+            predicate: NamedNode::new(format!("{POKE}pastValues"))?,
+            object: past_id.as_ref().into(),
+        });
+        triples.push(create_type_triple(past_id.as_ref(), "PastMoveValues")?);
+        triples.push(Triple {
+            subject: past_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}changedInVersionGroup"))?,
+            object: NamedNode::new(past.version_group.url)?.into(),
+        });
+        if let Some(accuracy) = past.accuracy {
             triples.push(Triple {
-                subject: move_id.into(),
-                predicate: NamedNode::new(format!("{POKE}flinchChance"))?,
-                object: Literal::new_simple_literal(meta.flinch_chance.to_string()).into(),
+                subject: past_id.as_ref().into(),
+                predicate: NamedNode::new(format!("{POKEMONKG}accuracy"))?,
+                object: Literal::new_typed_literal(accuracy.to_string(), xsd::INTEGER).into(),
             });
+        }
+        if let Some(effect_chance) = past.effect_chance {
             triples.push(Triple {
-                subject: move_id.into(),
-                predicate: NamedNode::new(format!("{POKE}statChance"))?,
-                object: Literal::new_typed_literal(meta.stat_chance.to_string(), xsd::INTEGER)
-                    .into(),
+                subject: past_id.as_ref().into(),
+                predicate: NamedNode::new(format!("{POKE}effectChance"))?,
+                object: Literal::new_typed_literal(effect_chance.to_string(), xsd::INTEGER).into(),
             });
-            if let Some(hits) = meta.min_hits {
-                triples.push(Triple {
-                    subject: move_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}minHits"))?,
-                    object: Literal::new_typed_literal(hits.to_string(), xsd::INTEGER).into(),
-                });
-            }
-            if let Some(hits) = meta.max_hits {
-                triples.push(Triple {
-                    subject: move_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}maxHits"))?,
-                    object: Literal::new_typed_literal(hits.to_string(), xsd::INTEGER).into(),
-                });
-            }
-            if let Some(turns) = meta.min_turns {
-                triples.push(Triple {
-                    subject: move_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}minTurns"))?,
-                    object: Literal::new_typed_literal(turns.to_string(), xsd::INTEGER).into(),
-                });
-            }
-            if let Some(turns) = meta.max_turns {
-                triples.push(Triple {
-                    subject: move_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}maxTurns"))?,
-                    object: Literal::new_typed_literal(turns.to_string(), xsd::INTEGER).into(),
-                });
-            }
-        }
-        // names
-        for n in move_json.names {
-            if n.language.name == "en" {
-                triples.push(Triple {
-                    subject: move_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}names"))?,
-                    object: Literal::new_simple_literal(n.name).into(),
-                });
-            }
         }
-        // TODO past_values
-        for stat in move_json.stat_changes.clone() {
-            let stat_change_id = NamedNode::new(stat.stat.url)?;
+        if let Some(pp) = past.pp {
             triples.push(Triple {
-                subject: move_id.into(),
-                predicate: NamedNode::new(format!("{POKE}statChanges"))?,
-                object: stat_change_id.as_ref().into(),
+                subject: past_id.as_ref().into(),
+                predicate: NamedNode::new(format!("{POKEMONKG}basePowerPoints"))?,
+                object: Literal::new_typed_literal(pp.to_string(), xsd::INTEGER).into(),
             });
+        }
+        if let Some(power) = past.power {
             triples.push(Triple {
-                subject: stat_change_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-                object: Literal::new_simple_literal(stat.stat.name).into(),
+                subject: past_id.as_ref().into(),
+                predicate: NamedNode::new(format!("{POKEMONKG}basePower"))?,
+                object: Literal::new_typed_literal(power.to_string(), xsd::INTEGER).into(),
             });
+        }
+        if let Some(type_) = past.type_ {
             triples.push(Triple {
-                subject: stat_change_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{POKE}change"))?,
-                object: Literal::new_typed_literal(stat.change.to_string(), xsd::INTEGER).into(),
+                subject: past_id.as_ref().into(),
+                predicate: NamedNode::new(format!("{POKEMONKG}hasType"))?,
+                object: NamedNode::new(type_.url)?.into(),
             });
         }
-        // TODO super_contest_effect
-
-        // move_target
+        for effect in past.effect_entries {
+            // TODO only english for now
+            if effect.language.name == "en" {
+                triples.extend(localized_to_nt(
+                    past_id.as_ref().into(),
+                    NamedNode::new(format!("{POKEMONKG}effectDescription"))?,
+                    effect.effect,
+                    &effect.language,
+                )?);
+            }
+        }
+    }
+    for stat in move_json.stat_changes.clone() {
+        let stat_change_id = NamedNode::new(stat.stat.url)?;
         triples.push(Triple {
             subject: move_id.into(),
-            predicate: NamedNode::new(format!("{POKE}target"))?,
-            object: NamedNode::new(move_json.target.url.clone())?.into(),
+            predicate: NamedNode::new(format!("{POKE}statChanges"))?,
+            object: stat_change_id.as_ref().into(),
         });
-
         triples.push(Triple {
-            subject: move_id.into(),
-            predicate: NamedNode::new(format!("{POKEMONKG}hasType"))?,
-            object: NamedNode::new(move_json.type_.url)?.into(),
+            subject: stat_change_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+            object: Literal::new_simple_literal(stat.stat.name).into(),
+        });
+        triples.push(Triple {
+            subject: stat_change_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}change"))?,
+            object: Literal::new_typed_literal(stat.change.to_string(), xsd::INTEGER).into(),
         });
-
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
-        }
     }
-    Ok(())
+    // TODO super_contest_effect
+
+    // move_target
+    triples.push(Triple {
+        subject: move_id.into(),
+        predicate: NamedNode::new(format!("{POKE}target"))?,
+        object: NamedNode::new(move_json.target.url.clone())?.into(),
+    });
+
+    triples.push(Triple {
+        subject: move_id.into(),
+        predicate: NamedNode::new(format!("{POKEMONKG}hasType"))?,
+        object: NamedNode::new(move_json.type_.url)?.into(),
+    });
+
+    send_resource(sink, url, triples)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_moves() {
-        assert!((move_to_nt(
+        let mut graph = Graph::new();
+        move_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("moves", &graph);
     }
 }
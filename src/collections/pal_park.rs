@@ -1,26 +1,28 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
 use oxrdf::{BlankNode, Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use rustemon::model::locations::PalParkArea;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
-use crate::create_type_triple;
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
 use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
 
 pub async fn pal_park_area_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let areas = match rustemon::locations::pal_park_area::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let areas = match client.get_all_entries::<PalParkArea>("pal-park-area").await {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all pal park areas: {:?}", e);
-            return Err(e.into());
+            return failures.record("pal-park-area", e);
         }
     };
     let len = areas.len();
@@ -30,87 +32,97 @@ pub async fn pal_park_area_to_nt(
     for (index, p) in areas.into_iter().enumerate() {
         pb.set_message(format!("pal park area {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples: Vec<Triple> = vec![];
-        let area_id = NamedNodeRef::new(p.url.as_str())?;
-        let area_json = match p.follow(&client).await {
-            Ok(list) => list,
-            Err(e) => {
-                println!("error getting pal park area info for {}: {e}", &p.url);
-                return Err(e.into());
-            }
-        };
-        // Add rdf:type declaration
-        triples.push(create_type_triple(area_id, "PalParkArea")?);
+        failures.skip_failed(
+            "pal-park-area",
+            Collection::PalParkArea
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Converts the pal park area at `url`, e.g. `https://pokeapi.co/api/v2/pal-park-area/1/`.
+pub async fn pal_park_area_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let area_id = NamedNodeRef::new(url)?;
+    let area_json: PalParkArea = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(area_id, "PalParkArea")?);
+
+    triples.push(Triple {
+        subject: area_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(area_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: area_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(area_json.name).into(),
+    });
 
+    for name in area_json.names {
+        // TODO only english for now
+        if name.language.name == "en" {
+            triples.extend(localized_to_nt(
+                area_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                name.name,
+                &name.language,
+            )?);
+        }
+    }
+
+    for (i, enc) in area_json.pokemon_encounters.into_iter().enumerate() {
+        let enc_id = BlankNode::new(format!("palparkarea{}_pokemonencounter{}", area_json.id, i))?;
         triples.push(Triple {
             subject: area_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(area_json.id.to_string(), xsd::INTEGER).into(),
+            predicate: NamedNode::new(format!("{POKE}pokemonEncounters"))?,
+            object: enc_id.as_ref().into(),
         });
         triples.push(Triple {
-            subject: area_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(area_json.name).into(),
+            subject: enc_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}baseScore"))?,
+            object: Literal::new_typed_literal(enc.base_score.to_string(), xsd::INTEGER).into(),
+        });
+        triples.push(Triple {
+            subject: enc_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}rate"))?,
+            object: Literal::new_typed_literal(enc.rate.to_string(), xsd::INTEGER).into(),
+        });
+        triples.push(Triple {
+            subject: enc_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}pokemonSpecies"))?,
+            object: NamedNode::new(enc.pokemon_species.url.as_str())?.into(),
         });
-
-        for name in area_json.names {
-            // TODO only english for now
-            if name.language.name == "en" {
-                triples.push(Triple {
-                    subject: area_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}names"))?,
-                    object: Literal::new_simple_literal(name.name).into(),
-                });
-            }
-        }
-
-        for (i, enc) in area_json.pokemon_encounters.into_iter().enumerate() {
-            let enc_id =
-                BlankNode::new(format!("palparkarea{}_pokemonencounter{}", area_json.id, i))?;
-            triples.push(Triple {
-                subject: area_id.into(),
-                predicate: NamedNode::new(format!("{POKE}pokemonEncounters"))?,
-                object: enc_id.as_ref().into(),
-            });
-            triples.push(Triple {
-                subject: enc_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{POKE}baseScore"))?,
-                object: Literal::new_typed_literal(enc.base_score.to_string(), xsd::INTEGER).into(),
-            });
-            triples.push(Triple {
-                subject: enc_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{POKE}rate"))?,
-                object: Literal::new_typed_literal(enc.rate.to_string(), xsd::INTEGER).into(),
-            });
-            triples.push(Triple {
-                subject: enc_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{POKE}pokemonSpecies"))?,
-                object: NamedNode::new(enc.pokemon_species.url.as_str())?.into(),
-            });
-        }
-
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
-        }
     }
 
-    Ok(())
+    send_resource(sink, url, triples)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_pal_park_areas() {
-        assert!((pal_park_area_to_nt(
+        let mut graph = Graph::new();
+        pal_park_area_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("pal_park", &graph);
     }
 }
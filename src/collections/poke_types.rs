@@ -1,179 +1,347 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
-use oxrdf::{BlankNode, Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use oxrdf::{
+    BlankNode, BlankNodeRef, Literal, NamedNode, NamedNodeRef, NamedOrBlankNodeRef, Triple,
+};
+use rustemon::model::games::Generation;
+use rustemon::model::pokemon::{Type, TypeRelations};
+use rustemon::model::resource::NamedApiResource;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
-use crate::create_type_triple;
+use crate::client::Client;
+use crate::collections::send_resource;
+use crate::failures::Failures;
+use crate::resource_id;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
 use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
 
 pub async fn type_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let all_types = match rustemon::pokemon::type_::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_types = match client.get_all_entries::<Type>("type").await {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all types: {:?}", e);
-            return Err(e.into());
+            return failures.record("type", e);
         }
     };
     let len = all_types.len();
     let pb =
         bar.add(ProgressBar::new(len.try_into().unwrap()).with_style(crate::create_bar_style()));
     pb.finish_with_message("done");
+    // kept around for the effectiveness matrices once every type has been fetched
+    let mut matchups: Vec<TypeMatchups> = vec![];
     for (index, t) in all_types.into_iter().enumerate() {
-        //if !self.types.contains(&t.type_.url) {
         pb.set_message(format!("type {}/{}", index + 1, len));
         pb.inc(1);
+        let converted = convert_type(&client, &t.url, sink).await;
+        failures.skip_failed("type", converted.map(|m| matchups.push(m)))?;
+    }
+
+    // effectiveness matrix, only for types that take part in battles (skips unknown, shadow, ...)
+    let battle_types: Vec<&TypeMatchups> = matchups
+        .iter()
+        .filter(|m| has_damage_relations(&m.relations))
+        .collect();
+    for attacker in &battle_types {
+        let mut triples = vec![];
+        for defender in &battle_types {
+            let matchup_id =
+                BlankNode::new(format!("type{}_against_type{}", attacker.id, defender.id))?;
+            triples.extend(effectiveness_to_nt(
+                matchup_id.as_ref(),
+                &attacker.url,
+                &defender.url,
+                damage_multiplier(&attacker.relations, &defender.url),
+            )?);
+        }
+        send_resource(sink, &format!("{}#effectiveness", attacker.url), triples)?;
+    }
+
+    // historical type charts, one matrix per generation holding only the types that existed
+    // then and the relations that applied then
+    let all_generations = match client.get_all_entries::<Generation>("generation").await {
+        Ok(list) => list,
+        Err(e) => {
+            return failures.record("type", e);
+        }
+    };
+    for g in all_generations {
+        let generation = resource_id(&g.url)?;
         let mut triples = vec![];
-        //self.types.insert(t.url.clone());
-        let type_id = NamedNodeRef::new(&t.url)?;
-        let type_json = match t.follow(&client).await {
-            Ok(v) => v,
-            Err(e) => {
-                eprintln!("Error getting type info for {}: {e}", &t.url);
-                return Err(e.into());
+        let in_generation: Vec<&&TypeMatchups> = battle_types
+            .iter()
+            .filter(|m| m.generation <= generation)
+            .collect();
+        for attacker in &in_generation {
+            let relations = attacker.relations_in(generation);
+            for defender in &in_generation {
+                let matchup_id = BlankNode::new(format!(
+                    "type{}_against_type{}_generation{}",
+                    attacker.id, defender.id, generation
+                ))?;
+                triples.extend(effectiveness_to_nt(
+                    matchup_id.as_ref(),
+                    &attacker.url,
+                    &defender.url,
+                    damage_multiplier(relations, &defender.url),
+                )?);
+                triples.push(Triple {
+                    subject: matchup_id.as_ref().into(),
+                    predicate: NamedNode::new(format!("{POKE}generation"))?,
+                    object: NamedNode::new(g.url.as_str())?.into(),
+                });
             }
-        };
-        // Add rdf:type declaration
-        triples.push(create_type_triple(type_id, "PokemonType")?);
+        }
+        send_resource(sink, &format!("{}#type-chart", g.url), triples)?;
+    }
+    Ok(())
+}
+
+/// Converts the type at `url`, e.g. `https://pokeapi.co/api/v2/type/1/`. The effectiveness
+/// matrices need every type and are left out.
+pub async fn type_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    convert_type(client, url, sink).await.map(|_| ())
+}
 
+// Converts one type and hands back what the effectiveness matrices need from it
+async fn convert_type(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<TypeMatchups, Error> {
+    let mut triples = vec![];
+    let type_id = NamedNodeRef::new(url)?;
+    let type_json: Type = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(type_id, "PokemonType")?);
+
+    triples.push(Triple {
+        subject: type_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(type_json.name).into(),
+    });
+    triples.push(Triple {
+        subject: type_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(type_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.extend(damage_relations_to_nt(
+        type_id.into(),
+        &type_json.damage_relations,
+    )?);
+
+    // past_damage_relations, each entry holds up to and including its generation
+    let mut past_relations = vec![];
+    for (i, past) in type_json.past_damage_relations.into_iter().enumerate() {
+        let past_id = BlankNode::new(format!("type{}_pastDamageRelations{}", type_json.id, i))?;
         triples.push(Triple {
             subject: type_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(type_json.name).into(),
+            predicate: NamedNode::new(format!("{POKE}pastDamageRelations"))?,
+            object: past_id.as_ref().into(),
         });
         triples.push(Triple {
-            subject: type_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(type_json.id.to_string(), xsd::INTEGER).into(),
+            subject: past_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}generation"))?,
+            object: NamedNode::new(past.generation.url.as_str())?.into(),
         });
-        for m in type_json.damage_relations.double_damage_from.clone() {
-            triples.push(Triple {
-                subject: type_id.into(),
-                predicate: NamedNode::new(format!("{POKE}doubleDamageFrom"))?,
-                object: Literal::new_simple_literal(m.url).into(),
-            });
-        }
-        for m in type_json.damage_relations.double_damage_to.clone() {
-            triples.push(Triple {
-                subject: type_id.into(),
-                predicate: NamedNode::new(format!("{POKE}doubleDamageTo"))?,
-                object: Literal::new_simple_literal(m.url).into(),
-            });
-        }
-        for m in type_json.damage_relations.half_damage_from.clone() {
-            triples.push(Triple {
-                subject: type_id.into(),
-                predicate: NamedNode::new(format!("{POKE}halfDamageFrom"))?,
-                object: Literal::new_simple_literal(m.url).into(),
-            });
-        }
-        for m in type_json.damage_relations.half_damage_to.clone() {
-            triples.push(Triple {
-                subject: type_id.into(),
-                predicate: NamedNode::new(format!("{POKE}halfDamageTo"))?,
-                object: Literal::new_simple_literal(m.url).into(),
-            });
-        }
-        for m in type_json.damage_relations.no_damage_from.clone() {
-            triples.push(Triple {
-                subject: type_id.into(),
-                predicate: NamedNode::new(format!("{POKE}noDamageFrom"))?,
-                object: Literal::new_simple_literal(m.url).into(),
-            });
-        }
-        for m in type_json.damage_relations.no_damage_to.clone() {
-            triples.push(Triple {
-                subject: type_id.into(),
-                predicate: NamedNode::new(format!("{POKE}noDamageTo"))?,
-                object: Literal::new_simple_literal(m.url).into(),
-            });
-        }
-
-        // TODO past_damage_relations
+        triples.extend(damage_relations_to_nt(
+            past_id.as_ref().into(),
+            &past.damage_relations,
+        )?);
+        past_relations.push((resource_id(&past.generation.url)?, past.damage_relations));
+    }
+    let matchups = TypeMatchups {
+        url: url.to_string(),
+        id: type_json.id,
+        generation: resource_id(&type_json.generation.url)?,
+        relations: type_json.damage_relations.clone(),
+        past_relations,
+    };
 
-        for (i, gi) in type_json.game_indices.into_iter().enumerate() {
-            let gi_id = BlankNode::new(format!("type{}_gameindex{}", type_json.id, i))?;
-            triples.push(Triple {
-                subject: type_id.into(),
-                predicate: NamedNode::new(format!("{POKE}gameIndex"))?,
-                object: gi_id.as_ref().into(),
-            });
-            triples.push(Triple {
-                subject: gi_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{POKE}index"))?,
-                object: Literal::new_typed_literal(gi.game_index.to_string(), xsd::INTEGER).into(),
-            });
-            triples.push(Triple {
-                subject: gi_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{POKE}generation"))?,
-                object: NamedNode::new(gi.generation.url)?.into(),
-            });
-        }
+    for (i, gi) in type_json.game_indices.into_iter().enumerate() {
+        let gi_id = BlankNode::new(format!("type{}_gameindex{}", type_json.id, i))?;
         triples.push(Triple {
             subject: type_id.into(),
+            predicate: NamedNode::new(format!("{POKE}gameIndex"))?,
+            object: gi_id.as_ref().into(),
+        });
+        triples.push(Triple {
+            subject: gi_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}index"))?,
+            object: Literal::new_typed_literal(gi.game_index.to_string(), xsd::INTEGER).into(),
+        });
+        triples.push(Triple {
+            subject: gi_id.as_ref().into(),
             predicate: NamedNode::new(format!("{POKE}generation"))?,
-            object: NamedNode::new(type_json.generation.url)?.into(),
+            object: NamedNode::new(gi.generation.url)?.into(),
         });
-        for n in type_json.names {
-            // TODO only english for now
-            if n.language.name == "en" {
-                triples.push(Triple {
-                    subject: type_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}names"))?,
-                    object: Literal::new_simple_literal(n.name).into(),
-                });
-            }
-        }
-        if let Some(damage) = type_json.move_damage_class {
-            triples.push(Triple {
-                subject: type_id.into(),
-                predicate: NamedNode::new(format!("{POKE}damageClass"))?,
-                object: NamedNode::new(&damage.url)?.into(),
-            });
-        }
-        for p in type_json.pokemon {
-            triples.push(Triple {
-                subject: type_id.into(),
-                predicate: NamedNode::new(format!("{POKE}pokemon"))?,
-                object: NamedNode::new(p.pokemon.url)?.into(),
-            });
+    }
+    triples.push(Triple {
+        subject: type_id.into(),
+        predicate: NamedNode::new(format!("{POKE}generation"))?,
+        object: NamedNode::new(type_json.generation.url)?.into(),
+    });
+    for n in type_json.names {
+        // TODO only english for now
+        if n.language.name == "en" {
+            triples.extend(localized_to_nt(
+                type_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                n.name,
+                &n.language,
+            )?);
         }
-        for m in type_json.moves {
+    }
+    if let Some(damage) = type_json.move_damage_class {
+        triples.push(Triple {
+            subject: type_id.into(),
+            predicate: NamedNode::new(format!("{POKE}damageClass"))?,
+            object: NamedNode::new(&damage.url)?.into(),
+        });
+    }
+    for p in type_json.pokemon {
+        triples.push(Triple {
+            subject: type_id.into(),
+            predicate: NamedNode::new(format!("{POKE}pokemon"))?,
+            object: NamedNode::new(p.pokemon.url)?.into(),
+        });
+    }
+    for m in type_json.moves {
+        triples.push(Triple {
+            subject: type_id.into(),
+            predicate: NamedNode::new(format!("{POKE}move"))?,
+            object: NamedNode::new(m.url)?.into(),
+        });
+    }
+    send_resource(sink, url, triples)?;
+    Ok(matchups)
+}
+
+struct TypeMatchups {
+    url: String,
+    id: i64,
+    // generation the type was introduced in
+    generation: i64,
+    relations: TypeRelations,
+    // (last generation the relations applied to, relations)
+    past_relations: Vec<(i64, TypeRelations)>,
+}
+
+impl TypeMatchups {
+    // The relations in effect during `generation`: the earliest past entry that still covers
+    // it, or the current ones when no past entry does.
+    fn relations_in(&self, generation: i64) -> &TypeRelations {
+        self.past_relations
+            .iter()
+            .filter(|(until, _)| *until >= generation)
+            .min_by_key(|(until, _)| *until)
+            .map(|(_, relations)| relations)
+            .unwrap_or(&self.relations)
+    }
+}
+
+fn damage_relations_to_nt(
+    subject: NamedOrBlankNodeRef,
+    relations: &TypeRelations,
+) -> Result<Vec<Triple>, Error> {
+    let mut triples = vec![];
+    for (predicate, types) in [
+        ("doubleDamageFrom", &relations.double_damage_from),
+        ("doubleDamageTo", &relations.double_damage_to),
+        ("halfDamageFrom", &relations.half_damage_from),
+        ("halfDamageTo", &relations.half_damage_to),
+        ("noDamageFrom", &relations.no_damage_from),
+        ("noDamageTo", &relations.no_damage_to),
+    ] {
+        for m in types {
             triples.push(Triple {
-                subject: type_id.into(),
-                predicate: NamedNode::new(format!("{POKE}move"))?,
-                object: NamedNode::new(m.url)?.into(),
+                subject: subject.into(),
+                predicate: NamedNode::new(format!("{POKE}{predicate}"))?,
+                object: NamedNode::new(m.url.as_str())?.into(),
             });
         }
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
-        }
     }
-    Ok(())
+    Ok(triples)
+}
+
+fn has_damage_relations(relations: &TypeRelations) -> bool {
+    !(relations.double_damage_from.is_empty()
+        && relations.double_damage_to.is_empty()
+        && relations.half_damage_from.is_empty()
+        && relations.half_damage_to.is_empty()
+        && relations.no_damage_from.is_empty()
+        && relations.no_damage_to.is_empty())
+}
+
+// xsd:decimal lexical form of the multiplier applied when the attacking type's
+// relations hit `defender`
+fn damage_multiplier(relations: &TypeRelations, defender: &str) -> &'static str {
+    let hits = |types: &Vec<NamedApiResource<Type>>| types.iter().any(|t| t.url == defender);
+    if hits(&relations.no_damage_to) {
+        "0"
+    } else if hits(&relations.half_damage_to) {
+        "0.5"
+    } else if hits(&relations.double_damage_to) {
+        "2"
+    } else {
+        "1"
+    }
+}
+
+fn effectiveness_to_nt(
+    matchup_id: BlankNodeRef,
+    attacker: &str,
+    defender: &str,
+    multiplier: &str,
+) -> Result<Vec<Triple>, Error> {
+    Ok(vec![
+        create_type_triple(matchup_id, "TypeEffectiveness")?,
+        Triple {
+            subject: matchup_id.into(),
+            predicate: NamedNode::new(format!("{POKE}attackingType"))?,
+            object: NamedNode::new(attacker)?.into(),
+        },
+        Triple {
+            subject: matchup_id.into(),
+            predicate: NamedNode::new(format!("{POKE}defendingType"))?,
+            object: NamedNode::new(defender)?.into(),
+        },
+        Triple {
+            subject: matchup_id.into(),
+            predicate: NamedNode::new(format!("{POKE}damageMultiplier"))?,
+            object: Literal::new_typed_literal(multiplier, xsd::DECIMAL).into(),
+        },
+    ])
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_poke_types() {
-        assert!((type_to_nt(
+        let mut graph = Graph::new();
+        type_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("poke_types", &graph);
     }
 }
@@ -0,0 +1,152 @@
+use indicatif::{MultiProgress, ProgressBar};
+use oxrdf::vocab::xsd;
+use oxrdf::{BlankNode, Literal, NamedNode, NamedNodeRef, Triple};
+use rustemon::model::pokemon::PokeathlonStat;
+use std::sync::Arc;
+
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
+use crate::POKE;
+use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
+
+pub async fn pokeathlon_stat_to_nt(
+    bar: &MultiProgress,
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_stats = match client
+        .get_all_entries::<PokeathlonStat>("pokeathlon-stat")
+        .await
+    {
+        Ok(list) => list,
+        Err(e) => {
+            return failures.record("pokeathlon-stat", e);
+        }
+    };
+    let len = all_stats.len();
+    let pb =
+        bar.add(ProgressBar::new(len.try_into().unwrap()).with_style(crate::create_bar_style()));
+    pb.finish_with_message("done");
+    for (index, p) in all_stats.into_iter().enumerate() {
+        pb.set_message(format!("pokeathlon stat {}/{}", index + 1, len));
+        pb.inc(1);
+        failures.skip_failed(
+            "pokeathlon-stat",
+            Collection::PokeathlonStat
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Converts the pokeathlon stat at `url`, e.g. `https://pokeapi.co/api/v2/pokeathlon-stat/1/`.
+pub async fn pokeathlon_stat_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let stat_id = NamedNodeRef::new(url)?;
+    let stat_json: PokeathlonStat = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(stat_id, "PokeathlonStat")?);
+
+    triples.push(Triple {
+        subject: stat_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(stat_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: stat_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(stat_json.name).into(),
+    });
+
+    for name in stat_json.names {
+        // TODO only english for now
+        if name.language.name == "en" {
+            triples.extend(localized_to_nt(
+                stat_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                name.name,
+                &name.language,
+            )?);
+        }
+    }
+
+    for (i, increase) in stat_json.affecting_natures.increase.into_iter().enumerate() {
+        let affect_id = BlankNode::new(format!(
+            "pokeathlonstat{}_increasedByNature{}",
+            stat_json.id, i
+        ))?;
+        triples.push(Triple {
+            subject: stat_id.into(),
+            predicate: NamedNode::new(format!("{POKE}increasedByNature"))?,
+            object: affect_id.as_ref().into(),
+        });
+        triples.push(Triple {
+            subject: affect_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}maxChange"))?,
+            object: Literal::new_typed_literal(increase.max_change.to_string(), xsd::INTEGER)
+                .into(),
+        });
+        triples.push(Triple {
+            subject: affect_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}nature"))?,
+            object: NamedNode::new(increase.nature.url)?.into(),
+        });
+    }
+    for (i, decrease) in stat_json.affecting_natures.decrease.into_iter().enumerate() {
+        let affect_id = BlankNode::new(format!(
+            "pokeathlonstat{}_decreasedByNature{}",
+            stat_json.id, i
+        ))?;
+        triples.push(Triple {
+            subject: stat_id.into(),
+            predicate: NamedNode::new(format!("{POKE}decreasedByNature"))?,
+            object: affect_id.as_ref().into(),
+        });
+        triples.push(Triple {
+            subject: affect_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}maxChange"))?,
+            object: Literal::new_typed_literal(decrease.max_change.to_string(), xsd::INTEGER)
+                .into(),
+        });
+        triples.push(Triple {
+            subject: affect_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}nature"))?,
+            object: NamedNode::new(decrease.nature.url)?.into(),
+        });
+    }
+
+    send_resource(sink, url, triples)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
+
+    #[tokio::test]
+    async fn test_pokeathlon_stats() {
+        let mut graph = Graph::new();
+        pokeathlon_stat_to_nt(
+            &MultiProgress::new(),
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
+        )
+        .await
+        .unwrap();
+        fixtures::assert_golden("pokeathlon_stats", &graph);
+    }
+}
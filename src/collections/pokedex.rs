@@ -1,26 +1,28 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
 use oxrdf::{BlankNode, Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use rustemon::model::games::Pokedex;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
-use crate::create_type_triple;
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
 use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
 
 pub async fn pokedex_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let all_pokedexes = match rustemon::games::pokedex::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_pokedexes = match client.get_all_entries::<Pokedex>("pokedex").await {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all pokedexes: {:?}", e);
-            return Err(e.into());
+            return failures.record("pokedex", e);
         }
     };
     let len = all_pokedexes.len();
@@ -30,110 +32,121 @@ pub async fn pokedex_to_nt(
     for (index, p) in all_pokedexes.into_iter().enumerate() {
         pb.set_message(format!("pokedexes {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples: Vec<Triple> = vec![];
-        let pokedex_id = NamedNodeRef::new(p.url.as_str())?;
-        let pokedex_json = match p.follow(&client).await {
-            Ok(list) => list,
-            Err(e) => {
-                println!("error getting pokedex info for {}: {e}", &p.url);
-                return Err(e.into());
-            }
-        };
-        // Add rdf:type declaration
-        triples.push(create_type_triple(pokedex_id, "Pokedex")?);
+        failures.skip_failed(
+            "pokedex",
+            Collection::Pokedex
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+    Ok(())
+}
 
-        triples.push(Triple {
-            subject: pokedex_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(pokedex_json.id.to_string(), xsd::INTEGER).into(),
-        });
-        triples.push(Triple {
-            subject: pokedex_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(pokedex_json.name).into(),
-        });
+/// Converts the pokedex at `url`, e.g. `https://pokeapi.co/api/v2/pokedex/1/`.
+pub async fn pokedex_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let pokedex_id = NamedNodeRef::new(url)?;
+    let pokedex_json: Pokedex = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(pokedex_id, "Pokedex")?);
 
-        // TODO is_main_series
+    triples.push(Triple {
+        subject: pokedex_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(pokedex_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: pokedex_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(pokedex_json.name).into(),
+    });
 
-        for description in pokedex_json.descriptions {
-            // TODO only english for now
-            if description.language.name == "en" {
-                triples.push(Triple {
-                    subject: pokedex_id.into(),
-                    predicate: NamedNode::new(format!("{SCHEMA}description"))?,
-                    object: Literal::new_simple_literal(description.description).into(),
-                });
-            }
-        }
+    // TODO is_main_series
 
-        for name in pokedex_json.names {
-            // TODO only english for now
-            if name.language.name == "en" {
-                triples.push(Triple {
-                    subject: pokedex_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}names"))?,
-                    object: Literal::new_simple_literal(name.name).into(),
-                });
-            }
+    for description in pokedex_json.descriptions {
+        // TODO only english for now
+        if description.language.name == "en" {
+            triples.extend(localized_to_nt(
+                pokedex_id.into(),
+                NamedNode::new(format!("{SCHEMA}description"))?,
+                description.description,
+                &description.language,
+            )?);
         }
+    }
 
-        for (i, entry) in pokedex_json.pokemon_entries.into_iter().enumerate() {
-            let entry_id = BlankNode::new(format!("pokedex{}_entry{}", pokedex_json.id, i))?;
-            triples.push(Triple {
-                subject: pokedex_id.into(),
-                predicate: NamedNode::new(format!("{POKE}hasPokedexEntry"))?,
-                object: entry_id.as_ref().into(),
-            });
-            triples.push(Triple {
-                subject: entry_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{POKE}entryNumber"))?,
-                object: Literal::new_typed_literal(entry.entry_number.to_string(), xsd::INTEGER)
-                    .into(),
-            });
-            triples.push(Triple {
-                subject: entry_id.as_ref().into(),
-                predicate: NamedNode::new(format!("{POKE}species"))?,
-                object: NamedNode::new(entry.pokemon_species.url)?.into(),
-            });
+    for name in pokedex_json.names {
+        // TODO only english for now
+        if name.language.name == "en" {
+            triples.extend(localized_to_nt(
+                pokedex_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                name.name,
+                &name.language,
+            )?);
         }
+    }
 
-        if let Some(region) = pokedex_json.region {
-            triples.push(Triple {
-                subject: pokedex_id.into(),
-                predicate: NamedNode::new(format!("{POKE}region"))?,
-                object: NamedNode::new(region.url)?.into(),
-            });
-        }
+    for (i, entry) in pokedex_json.pokemon_entries.into_iter().enumerate() {
+        let entry_id = BlankNode::new(format!("pokedex{}_entry{}", pokedex_json.id, i))?;
+        triples.push(Triple {
+            subject: pokedex_id.into(),
+            predicate: NamedNode::new(format!("{POKE}hasPokedexEntry"))?,
+            object: entry_id.as_ref().into(),
+        });
+        triples.push(Triple {
+            subject: entry_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}entryNumber"))?,
+            object: Literal::new_typed_literal(entry.entry_number.to_string(), xsd::INTEGER).into(),
+        });
+        triples.push(Triple {
+            subject: entry_id.as_ref().into(),
+            predicate: NamedNode::new(format!("{POKE}species"))?,
+            object: NamedNode::new(entry.pokemon_species.url)?.into(),
+        });
+    }
 
-        for group in pokedex_json.version_groups {
-            triples.push(Triple {
-                subject: pokedex_id.into(),
-                predicate: NamedNode::new(format!("{POKE}versionGroup"))?,
-                object: NamedNode::new(group.url)?.into(),
-            });
-        }
+    if let Some(region) = pokedex_json.region {
+        triples.push(Triple {
+            subject: pokedex_id.into(),
+            predicate: NamedNode::new(format!("{POKE}region"))?,
+            object: NamedNode::new(region.url)?.into(),
+        });
+    }
 
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
-        }
+    for group in pokedex_json.version_groups {
+        triples.push(Triple {
+            subject: pokedex_id.into(),
+            predicate: NamedNode::new(format!("{POKE}versionGroup"))?,
+            object: NamedNode::new(group.url)?.into(),
+        });
     }
-    Ok(())
+
+    send_resource(sink, url, triples)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_pokedex() {
-        assert!((pokedex_to_nt(
+        let mut graph = Graph::new();
+        pokedex_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("pokedex", &graph);
     }
 }
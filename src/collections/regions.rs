@@ -1,26 +1,28 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
 use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use rustemon::model::locations::Region;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
-use crate::create_type_triple;
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
 use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
 
 pub async fn region_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let all_regions = match rustemon::locations::region::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_regions = match client.get_all_entries::<Region>("region").await {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all regions: {:?}", e);
-            return Err(e.into());
+            return failures.record("region", e);
         }
     };
     let len = all_regions.len();
@@ -30,68 +32,79 @@ pub async fn region_to_nt(
     for (index, p) in all_regions.into_iter().enumerate() {
         pb.set_message(format!("region {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples: Vec<Triple> = vec![];
-        let region_id = NamedNodeRef::new(p.url.as_str())?;
-        let region_json = match p.follow(&client).await {
-            Ok(list) => list,
-            Err(e) => {
-                println!("error getting region info for {}: {e}", &p.url);
-                return Err(e.into());
-            }
-        };
+        failures.skip_failed(
+            "region",
+            Collection::Region
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
 
-        // Add rdf:type declaration
-        triples.push(create_type_triple(region_id, "Region")?);
+    Ok(())
+}
 
+/// Converts the region at `url`, e.g. `https://pokeapi.co/api/v2/region/1/`.
+pub async fn region_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let region_id = NamedNodeRef::new(url)?;
+    let region_json: Region = client.follow(url).await?;
+
+    // Add rdf:type declaration
+    triples.push(create_type_triple(region_id, "Region")?);
+
+    triples.push(Triple {
+        subject: region_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(region_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: region_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(region_json.name).into(),
+    });
+    for n in region_json.names {
+        // TODO only english for now
+        if n.language.name == "en" {
+            triples.extend(localized_to_nt(
+                region_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                n.name,
+                &n.language,
+            )?);
+        }
+    }
+    for l in region_json.locations {
         triples.push(Triple {
             subject: region_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(region_json.id.to_string(), xsd::INTEGER).into(),
-        });
-        triples.push(Triple {
-            subject: region_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(region_json.name).into(),
+            predicate: NamedNode::new(format!("{POKE}hasLocation"))?,
+            object: NamedNode::new(&l.url)?.into(),
         });
-        for n in region_json.names {
-            // TODO only english for now
-            if n.language.name == "en" {
-                triples.push(Triple {
-                    subject: region_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}names"))?,
-                    object: Literal::new_simple_literal(n.name).into(),
-                });
-            }
-        }
-        for l in region_json.locations {
-            triples.push(Triple {
-                subject: region_id.into(),
-                predicate: NamedNode::new(format!("{POKE}hasLocation"))?,
-                object: NamedNode::new(&l.url)?.into(),
-            });
-        }
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
-        }
     }
-
-    Ok(())
+    send_resource(sink, url, triples)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_regions() {
-        assert!((region_to_nt(
+        let mut graph = Graph::new();
+        region_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("regions", &graph);
     }
 }
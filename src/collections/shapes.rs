@@ -1,26 +1,31 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
 use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use rustemon::model::pokemon::PokemonShape;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
-use crate::create_type_triple;
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
 use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
 
 pub async fn shape_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let shapes = match rustemon::pokemon::pokemon_shape::get_all_entries(&client).await {
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let shapes = match client
+        .get_all_entries::<PokemonShape>("pokemon-shape")
+        .await
+    {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all shapes: {:?}", e);
-            return Err(e.into());
+            return failures.record("pokemon-shape", e);
         }
     };
     let len = shapes.len();
@@ -30,80 +35,92 @@ pub async fn shape_to_nt(
     for (index, p) in shapes.into_iter().enumerate() {
         pb.set_message(format!("shape {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples: Vec<Triple> = vec![];
-        let shape_id = NamedNodeRef::new(p.url.as_str())?;
-        let shape_json = match p.follow(&client).await {
-            Ok(list) => list,
-            Err(e) => {
-                println!("error getting shape info for {}: {e}", &p.url);
-                return Err(e.into());
-            }
-        };
-        // Add rdf:type declaration
-        triples.push(create_type_triple(shape_id, "PokemonShape")?);
+        failures.skip_failed(
+            "pokemon-shape",
+            Collection::PokemonShape
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+    Ok(())
+}
 
-        triples.push(Triple {
-            subject: shape_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(shape_json.id.to_string(), xsd::INTEGER).into(),
-        });
-        triples.push(Triple {
-            subject: shape_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(shape_json.name).into(),
-        });
+/// Converts the pokemon shape at `url`, e.g. `https://pokeapi.co/api/v2/pokemon-shape/1/`.
+pub async fn shape_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let shape_id = NamedNodeRef::new(url)?;
+    let shape_json: PokemonShape = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(shape_id, "PokemonShape")?);
 
-        for name in shape_json.awesome_names {
-            // TODO only english for now
-            if name.language.name == "en" {
-                triples.push(Triple {
-                    subject: shape_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}awsomeNames"))?,
-                    object: Literal::new_simple_literal(name.awesome_name).into(),
-                });
-            }
-        }
+    triples.push(Triple {
+        subject: shape_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(shape_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: shape_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(shape_json.name).into(),
+    });
 
-        for name in shape_json.names {
-            // TODO only english for now
-            if name.language.name == "en" {
-                triples.push(Triple {
-                    subject: shape_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}names"))?,
-                    object: Literal::new_simple_literal(name.name).into(),
-                });
-            }
+    for name in shape_json.awesome_names {
+        // TODO only english for now
+        if name.language.name == "en" {
+            triples.extend(localized_to_nt(
+                shape_id.into(),
+                NamedNode::new(format!("{POKE}awsomeNames"))?,
+                name.awesome_name,
+                &name.language,
+            )?);
         }
+    }
 
-        for species in shape_json.pokemon_species {
-            triples.push(Triple {
-                subject: shape_id.into(),
-                predicate: NamedNode::new(format!("{POKE}hasPokemonSpecies"))?,
-                object: NamedNodeRef::new(species.url.as_str())?.into(),
-            });
+    for name in shape_json.names {
+        // TODO only english for now
+        if name.language.name == "en" {
+            triples.extend(localized_to_nt(
+                shape_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                name.name,
+                &name.language,
+            )?);
         }
+    }
 
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
-        }
+    for species in shape_json.pokemon_species {
+        triples.push(Triple {
+            subject: shape_id.into(),
+            predicate: NamedNode::new(format!("{POKE}hasPokemonSpecies"))?,
+            object: NamedNodeRef::new(species.url.as_str())?.into(),
+        });
     }
-    Ok(())
+
+    send_resource(sink, url, triples)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_shapes() {
-        assert!((shape_to_nt(
+        let mut graph = Graph::new();
+        shape_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("shapes", &graph);
     }
 }
@@ -0,0 +1,511 @@
+use oxrdf::vocab::{self, xsd};
+use oxrdf::{BlankNodeRef, Literal, NamedNode, NamedNodeRef, Triple};
+use rustemon::model::pokemon::{PokemonFormSprites, PokemonSprites};
+use serde_json::Value;
+
+use crate::Error;
+use crate::POKE;
+use crate::POKEAPI;
+use crate::SCHEMA;
+
+// Where a sprite comes from. PokeAPI only names version groups by their JSON keys, so the ids
+// of the matching resources are spelled out here.
+pub enum SpriteSource {
+    // the flat sprites of a pokemon, form or item
+    Default,
+    // official-artwork, home, dream-world, showdown
+    Artwork(&'static str),
+    // sprites taken from a version group's games
+    VersionGroup(&'static str, i64),
+    // sprites taken from a single version, with the id of its version group and the version
+    Version(&'static str, i64, i64),
+    // menu icons, only grouped by generation
+    Generation(&'static str, i64),
+}
+
+impl SpriteSource {
+    fn name(&self) -> &'static str {
+        match self {
+            SpriteSource::Default => "default",
+            SpriteSource::Artwork(name)
+            | SpriteSource::VersionGroup(name, _)
+            | SpriteSource::Version(name, _, _)
+            | SpriteSource::Generation(name, _) => name,
+        }
+    }
+}
+
+// A sprite URL with the source it belongs to and the rustemon or JSON field it was read from,
+// e.g. "front_shiny_female"
+pub type Sprite = (SpriteSource, &'static str, String);
+
+// Every sprite as a schema:ImageObject attached to the subject with schema:image
+pub fn sprite_to_nt(
+    subject: NamedNodeRef,
+    sprite_id: BlankNodeRef,
+    sprite: &Sprite,
+) -> Result<Vec<Triple>, Error> {
+    let (source, variant, url) = sprite;
+    let mut triples = vec![
+        Triple {
+            subject: subject.into(),
+            predicate: NamedNode::new(format!("{SCHEMA}image"))?,
+            object: sprite_id.into(),
+        },
+        Triple {
+            subject: sprite_id.into(),
+            predicate: vocab::rdf::TYPE.into(),
+            object: NamedNode::new(format!("{SCHEMA}ImageObject"))?.into(),
+        },
+        Triple {
+            subject: sprite_id.into(),
+            predicate: NamedNode::new(format!("{SCHEMA}contentUrl"))?,
+            object: NamedNode::new(url)?.into(),
+        },
+    ];
+    if let Some(format) = encoding_format(url) {
+        triples.push(Triple {
+            subject: sprite_id.into(),
+            predicate: NamedNode::new(format!("{SCHEMA}encodingFormat"))?,
+            object: Literal::new_simple_literal(format).into(),
+        });
+    }
+
+    // variant, taken apart from the field name
+    let parts: Vec<&str> = variant.split('_').collect();
+    for side in parts.iter().filter(|p| matches!(**p, "front" | "back")) {
+        triples.push(Triple {
+            subject: sprite_id.into(),
+            predicate: NamedNode::new(format!("{POKE}spriteSide"))?,
+            object: Literal::new_simple_literal(*side).into(),
+        });
+    }
+    for style in parts
+        .iter()
+        .filter(|p| matches!(**p, "gray" | "transparent" | "animated"))
+    {
+        triples.push(Triple {
+            subject: sprite_id.into(),
+            predicate: NamedNode::new(format!("{POKE}spriteStyle"))?,
+            object: Literal::new_simple_literal(*style).into(),
+        });
+    }
+    triples.push(Triple {
+        subject: sprite_id.into(),
+        predicate: NamedNode::new(format!("{POKE}shiny"))?,
+        object: Literal::new_typed_literal(parts.contains(&"shiny").to_string(), xsd::BOOLEAN)
+            .into(),
+    });
+    triples.push(Triple {
+        subject: sprite_id.into(),
+        predicate: NamedNode::new(format!("{POKE}female"))?,
+        object: Literal::new_typed_literal(parts.contains(&"female").to_string(), xsd::BOOLEAN)
+            .into(),
+    });
+
+    // source
+    triples.push(Triple {
+        subject: sprite_id.into(),
+        predicate: NamedNode::new(format!("{POKE}spriteSource"))?,
+        object: Literal::new_simple_literal(source.name()).into(),
+    });
+    match source {
+        SpriteSource::VersionGroup(_, version_group) => {
+            triples.push(Triple {
+                subject: sprite_id.into(),
+                predicate: NamedNode::new(format!("{POKE}versionGroup"))?,
+                object: NamedNode::new(format!("{POKEAPI}version-group/{version_group}/"))?.into(),
+            });
+        }
+        SpriteSource::Version(_, version_group, version) => {
+            triples.push(Triple {
+                subject: sprite_id.into(),
+                predicate: NamedNode::new(format!("{POKE}versionGroup"))?,
+                object: NamedNode::new(format!("{POKEAPI}version-group/{version_group}/"))?.into(),
+            });
+            triples.push(Triple {
+                subject: sprite_id.into(),
+                predicate: NamedNode::new(format!("{POKE}version"))?,
+                object: NamedNode::new(format!("{POKEAPI}version/{version}/"))?.into(),
+            });
+        }
+        SpriteSource::Generation(_, generation) => {
+            triples.push(Triple {
+                subject: sprite_id.into(),
+                predicate: NamedNode::new(format!("{POKE}generation"))?,
+                object: NamedNode::new(format!("{POKEAPI}generation/{generation}/"))?.into(),
+            });
+        }
+        SpriteSource::Default | SpriteSource::Artwork(_) => {}
+    }
+
+    Ok(triples)
+}
+
+fn encoding_format(url: &str) -> Option<&'static str> {
+    match url.rsplit('.').next() {
+        Some("png") => Some("image/png"),
+        Some("svg") => Some("image/svg+xml"),
+        Some("gif") => Some("image/gif"),
+        _ => None,
+    }
+}
+
+// Keeps the sprites that are set, in the order they are listed
+fn collect_sprites(
+    sprites: &mut Vec<Sprite>,
+    source: impl Fn() -> SpriteSource,
+    fields: Vec<(&'static str, Option<String>)>,
+) {
+    for (variant, url) in fields {
+        if let Some(url) = url {
+            sprites.push((source(), variant, url));
+        }
+    }
+}
+
+// All sprites of a pokemon, including artwork and per-version sprites. `raw` is the `sprites`
+// object of the JSON, for the sprites the rustemon model has no fields for.
+pub fn pokemon_sprites(s: PokemonSprites, raw: &Value) -> Vec<Sprite> {
+    let field = |sprites: &Value, name: &str| sprites[name].as_str().map(str::to_string);
+    let mut sprites = vec![];
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::Default,
+        vec![
+            ("front_default", s.front_default),
+            ("front_shiny", s.front_shiny),
+            ("front_female", s.front_female),
+            ("front_shiny_female", s.front_shiny_female),
+            ("back_default", s.back_default),
+            ("back_shiny", s.back_shiny),
+            ("back_female", s.back_female),
+            ("back_shiny_female", s.back_shiny_female),
+        ],
+    );
+
+    // OtherSprites
+    let other = s.other;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::Artwork("dream-world"),
+        vec![
+            ("front_default", other.dream_world.front_default),
+            ("front_female", other.dream_world.front_female),
+        ],
+    );
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::Artwork("home"),
+        vec![
+            ("front_default", other.home.front_default),
+            ("front_female", other.home.front_female),
+            ("front_shiny", other.home.front_shiny),
+            ("front_shiny_female", other.home.front_shiny_female),
+        ],
+    );
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::Artwork("official-artwork"),
+        vec![
+            ("front_default", other.official_artwork.front_default),
+            (
+                "front_shiny",
+                field(&raw["other"]["official-artwork"], "front_shiny"),
+            ),
+        ],
+    );
+    let showdown = &raw["other"]["showdown"];
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::Artwork("showdown"),
+        vec![
+            ("front_default", field(showdown, "front_default")),
+            ("front_female", field(showdown, "front_female")),
+            ("front_shiny", field(showdown, "front_shiny")),
+            ("front_shiny_female", field(showdown, "front_shiny_female")),
+            ("back_default", field(showdown, "back_default")),
+            ("back_female", field(showdown, "back_female")),
+            ("back_shiny", field(showdown, "back_shiny")),
+            ("back_shiny_female", field(showdown, "back_shiny_female")),
+        ],
+    );
+
+    // VersionSprites
+    let versions = s.versions;
+    let v = versions.generation_i.red_blue;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::VersionGroup("red-blue", 1),
+        vec![
+            ("back_default", v.back_default),
+            ("back_gray", v.back_gray),
+            ("back_transparent", v.back_transparent),
+            ("front_default", v.front_default),
+            ("front_gray", v.front_gray),
+            ("front_transparent", v.front_transparent),
+        ],
+    );
+    let v = versions.generation_i.yellow;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::VersionGroup("yellow", 2),
+        vec![
+            ("back_default", v.back_default),
+            ("back_gray", v.back_gray),
+            ("back_transparent", v.back_transparent),
+            ("front_default", v.front_default),
+            ("front_gray", v.front_gray),
+            ("front_transparent", v.front_transparent),
+        ],
+    );
+    let v = versions.generation_ii.crystal;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::VersionGroup("crystal", 4),
+        vec![
+            ("back_default", v.back_default),
+            ("back_shiny", v.back_shiny),
+            ("back_transparent", v.back_transparent),
+            ("back_shiny_transparent", v.back_shiny_transparent),
+            ("front_default", v.front_default),
+            ("front_shiny", v.front_shiny),
+            ("front_transparent", v.front_transparent),
+            ("front_shiny_transparent", v.front_shiny_transparent),
+        ],
+    );
+    let v = versions.generation_ii.gold;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::Version("gold", 3, 4),
+        vec![
+            ("back_default", v.back_default),
+            ("back_shiny", v.back_shiny),
+            ("front_default", v.front_default),
+            ("front_shiny", v.front_shiny),
+            ("front_transparent", v.front_transparent),
+        ],
+    );
+    let v = versions.generation_ii.silver;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::Version("silver", 3, 5),
+        vec![
+            ("back_default", v.back_default),
+            ("back_shiny", v.back_shiny),
+            ("front_default", v.front_default),
+            ("front_shiny", v.front_shiny),
+            ("front_transparent", v.front_transparent),
+        ],
+    );
+    let v = versions.generation_iii.ruby_sapphire;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::VersionGroup("ruby-sapphire", 5),
+        vec![
+            ("back_default", v.back_default),
+            ("back_shiny", v.back_shiny),
+            ("front_default", v.front_default),
+            ("front_shiny", v.front_shiny),
+        ],
+    );
+    let v = versions.generation_iii.emerald;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::VersionGroup("emerald", 6),
+        vec![
+            ("front_default", v.front_default),
+            ("front_shiny", v.front_shiny),
+        ],
+    );
+    let v = versions.generation_iii.firered_leafgreen;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::VersionGroup("firered-leafgreen", 7),
+        vec![
+            ("back_default", v.back_default),
+            ("back_shiny", v.back_shiny),
+            ("front_default", v.front_default),
+            ("front_shiny", v.front_shiny),
+        ],
+    );
+    let v = versions.generation_iv.diamond_pearl;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::VersionGroup("diamond-pearl", 8),
+        vec![
+            ("back_default", v.back_default),
+            ("back_female", v.back_female),
+            ("back_shiny", v.back_shiny),
+            ("back_shiny_female", v.back_shiny_female),
+            ("front_default", v.front_default),
+            ("front_female", v.front_female),
+            ("front_shiny", v.front_shiny),
+            ("front_shiny_female", v.front_shiny_female),
+        ],
+    );
+    let v = versions.generation_iv.platinum;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::VersionGroup("platinum", 9),
+        vec![
+            ("back_default", v.back_default),
+            ("back_female", v.back_female),
+            ("back_shiny", v.back_shiny),
+            ("back_shiny_female", v.back_shiny_female),
+            ("front_default", v.front_default),
+            ("front_female", v.front_female),
+            ("front_shiny", v.front_shiny),
+            ("front_shiny_female", v.front_shiny_female),
+        ],
+    );
+    let v = versions.generation_iv.heartgold_soulsilver;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::VersionGroup("heartgold-soulsilver", 10),
+        vec![
+            ("back_default", v.back_default),
+            ("back_female", v.back_female),
+            ("back_shiny", v.back_shiny),
+            ("back_shiny_female", v.back_shiny_female),
+            ("front_default", v.front_default),
+            ("front_female", v.front_female),
+            ("front_shiny", v.front_shiny),
+            ("front_shiny_female", v.front_shiny_female),
+        ],
+    );
+    let v = versions.generation_v.black_white;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::VersionGroup("black-white", 11),
+        vec![
+            ("back_default", v.back_default),
+            ("back_female", v.back_female),
+            ("back_shiny", v.back_shiny),
+            ("back_shiny_female", v.back_shiny_female),
+            ("front_default", v.front_default),
+            ("front_female", v.front_female),
+            ("front_shiny", v.front_shiny),
+            ("front_shiny_female", v.front_shiny_female),
+            ("animated_back_default", v.animated.back_default),
+            ("animated_back_female", v.animated.back_female),
+            ("animated_back_shiny", v.animated.back_shiny),
+            ("animated_back_shiny_female", v.animated.back_shiny_female),
+            ("animated_front_default", v.animated.front_default),
+            ("animated_front_female", v.animated.front_female),
+            ("animated_front_shiny", v.animated.front_shiny),
+            ("animated_front_shiny_female", v.animated.front_shiny_female),
+        ],
+    );
+    let v = versions.generation_vi.x_y;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::VersionGroup("x-y", 15),
+        vec![
+            ("front_default", v.front_default),
+            ("front_female", v.front_female),
+            ("front_shiny", v.front_shiny),
+            ("front_shiny_female", v.front_shiny_female),
+        ],
+    );
+    let v = versions.generation_vi.omegaruby_alphasapphire;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::VersionGroup("omega-ruby-alpha-sapphire", 16),
+        vec![
+            ("front_default", v.front_default),
+            ("front_female", v.front_female),
+            ("front_shiny", v.front_shiny),
+            ("front_shiny_female", v.front_shiny_female),
+        ],
+    );
+    let v = versions.generation_vii.ultrasun_ultramoon;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::VersionGroup("ultra-sun-ultra-moon", 18),
+        vec![
+            ("front_default", v.front_default),
+            ("front_female", v.front_female),
+            ("front_shiny", v.front_shiny),
+            ("front_shiny_female", v.front_shiny_female),
+        ],
+    );
+    let v = versions.generation_vii.icons;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::Generation("icons", 7),
+        vec![
+            ("front_default", v.front_default),
+            ("front_female", v.front_female),
+        ],
+    );
+    let v = versions.generation_viii.icons;
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::Generation("icons", 8),
+        vec![
+            ("front_default", v.front_default),
+            ("front_female", v.front_female),
+        ],
+    );
+
+    sprites
+}
+
+// Forms only have the flat sprite set, their version group is already on the form itself
+pub fn form_sprites(s: PokemonFormSprites) -> Vec<Sprite> {
+    let mut sprites = vec![];
+    collect_sprites(
+        &mut sprites,
+        || SpriteSource::Default,
+        vec![
+            ("front_default", s.front_default),
+            ("front_shiny", s.front_shiny),
+            ("front_female", s.front_female),
+            ("front_shiny_female", s.front_shiny_female),
+            ("back_default", s.back_default),
+            ("back_shiny", s.back_shiny),
+            ("back_female", s.back_female),
+            ("back_shiny_female", s.back_shiny_female),
+        ],
+    );
+    sprites
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use oxrdf::BlankNode;
+
+    #[test]
+    fn test_sprite_to_nt() {
+        let pokemon = NamedNodeRef::new("https://pokeapi.co/api/v2/pokemon/25/").unwrap();
+        let sprite_id = BlankNode::new("pokemon25_sprite0").unwrap();
+        let sprite = (
+            SpriteSource::Version("gold", 3, 4),
+            "front_shiny",
+            "https://example.org/sprites/25.png".to_string(),
+        );
+        let lines: Vec<String> = sprite_to_nt(pokemon, sprite_id.as_ref(), &sprite)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.to_string())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "<https://pokeapi.co/api/v2/pokemon/25/> <https://schema.org/image> _:pokemon25_sprite0",
+                "_:pokemon25_sprite0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://schema.org/ImageObject>",
+                "_:pokemon25_sprite0 <https://schema.org/contentUrl> <https://example.org/sprites/25.png>",
+                "_:pokemon25_sprite0 <https://schema.org/encodingFormat> \"image/png\"",
+                "_:pokemon25_sprite0 <http://purl.org/pokemon/ontology#spriteSide> \"front\"",
+                "_:pokemon25_sprite0 <http://purl.org/pokemon/ontology#shiny> \"true\"^^<http://www.w3.org/2001/XMLSchema#boolean>",
+                "_:pokemon25_sprite0 <http://purl.org/pokemon/ontology#female> \"false\"^^<http://www.w3.org/2001/XMLSchema#boolean>",
+                "_:pokemon25_sprite0 <http://purl.org/pokemon/ontology#spriteSource> \"gold\"",
+                "_:pokemon25_sprite0 <http://purl.org/pokemon/ontology#versionGroup> <https://pokeapi.co/api/v2/version-group/3/>",
+                "_:pokemon25_sprite0 <http://purl.org/pokemon/ontology#version> <https://pokeapi.co/api/v2/version/4/>",
+            ]
+        );
+    }
+}
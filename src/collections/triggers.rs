@@ -1,27 +1,31 @@
 use indicatif::{MultiProgress, ProgressBar};
 use oxrdf::vocab::xsd;
 use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
-use rustemon::client::RustemonClient;
-use rustemon::Follow;
-use std::error::Error;
+use rustemon::model::evolution::EvolutionTrigger;
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
-use crate::create_type_triple;
+use crate::client::Client;
+use crate::collections::{send_resource, Collection};
+use crate::failures::Failures;
+use crate::sink::TripleSink;
+use crate::Error;
 use crate::POKE;
 use crate::SCHEMA;
+use crate::{create_type_triple, localized_to_nt};
 
 pub async fn trigger_to_nt(
     bar: &MultiProgress,
-    client: Arc<RustemonClient>,
-    tx: mpsc::UnboundedSender<String>,
-) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let all_triggers = match rustemon::evolution::evolution_trigger::get_all_entries(&client).await
+    client: Arc<Client>,
+    sink: &mut dyn TripleSink,
+    failures: &Failures,
+) -> Result<(), Error> {
+    let all_triggers = match client
+        .get_all_entries::<EvolutionTrigger>("evolution-trigger")
+        .await
     {
         Ok(list) => list,
         Err(e) => {
-            println!("error getting all evolution triggers: {:?}", e);
-            return Err(e.into());
+            return failures.record("evolution-trigger", e);
         }
     };
     let len = all_triggers.len();
@@ -31,69 +35,80 @@ pub async fn trigger_to_nt(
     for (index, p) in all_triggers.into_iter().enumerate() {
         pb.set_message(format!("triggers {}/{}", index + 1, len));
         pb.inc(1);
-        let mut triples: Vec<Triple> = vec![];
-        let trigger_id = NamedNodeRef::new(p.url.as_str())?;
-        let trigger_json = match p.follow(&client).await {
-            Ok(list) => list,
-            Err(e) => {
-                println!("error getting trigger info for {}: {e}", &p.url);
-                return Err(e.into());
-            }
-        };
-        // Add rdf:type declaration
-        triples.push(create_type_triple(trigger_id, "EvolutionTrigger")?);
+        failures.skip_failed(
+            "evolution-trigger",
+            Collection::EvolutionTrigger
+                .convert_resource(&client, &p.url, sink)
+                .await,
+        )?;
+    }
+    Ok(())
+}
 
-        triples.push(Triple {
-            subject: trigger_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
-            object: Literal::new_typed_literal(trigger_json.id.to_string(), xsd::INTEGER).into(),
-        });
-        triples.push(Triple {
-            subject: trigger_id.into(),
-            predicate: NamedNode::new(format!("{SCHEMA}name"))?,
-            object: Literal::new_simple_literal(trigger_json.name).into(),
-        });
+/// Converts the evolution trigger at `url`, e.g. `https://pokeapi.co/api/v2/evolution-trigger/1/`.
+pub async fn trigger_resource_to_nt(
+    client: &Client,
+    url: &str,
+    sink: &mut dyn TripleSink,
+) -> Result<(), Error> {
+    let mut triples: Vec<Triple> = vec![];
+    let trigger_id = NamedNodeRef::new(url)?;
+    let trigger_json: EvolutionTrigger = client.follow(url).await?;
+    // Add rdf:type declaration
+    triples.push(create_type_triple(trigger_id, "EvolutionTrigger")?);
 
-        for name in trigger_json.names {
-            // TODO only english for now
-            if name.language.name == "en" {
-                triples.push(Triple {
-                    subject: trigger_id.into(),
-                    predicate: NamedNode::new(format!("{POKE}names"))?,
-                    object: Literal::new_simple_literal(name.name).into(),
-                });
-            }
-        }
+    triples.push(Triple {
+        subject: trigger_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}identifier"))?,
+        object: Literal::new_typed_literal(trigger_json.id.to_string(), xsd::INTEGER).into(),
+    });
+    triples.push(Triple {
+        subject: trigger_id.into(),
+        predicate: NamedNode::new(format!("{SCHEMA}name"))?,
+        object: Literal::new_simple_literal(trigger_json.name).into(),
+    });
 
-        for species in trigger_json.pokemon_species {
-            triples.push(Triple {
-                subject: trigger_id.into(),
-                predicate: NamedNode::new(format!("{POKE}triggersSpecies"))?,
-                object: NamedNode::new(species.url)?.into(),
-            });
+    for name in trigger_json.names {
+        // TODO only english for now
+        if name.language.name == "en" {
+            triples.extend(localized_to_nt(
+                trigger_id.into(),
+                NamedNode::new(format!("{POKE}names"))?,
+                name.name,
+                &name.language,
+            )?);
         }
+    }
 
-        for t in triples {
-            tx.send(format!("{t} ."))
-                .map_err(|e| format!("Send error: {}", e))?
-        }
+    for species in trigger_json.pokemon_species {
+        triples.push(Triple {
+            subject: trigger_id.into(),
+            predicate: NamedNode::new(format!("{POKE}triggersSpecies"))?,
+            object: NamedNode::new(species.url)?.into(),
+        });
     }
-    Ok(())
+
+    send_resource(sink, url, triples)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use oxrdf::Graph;
 
     #[tokio::test]
     async fn test_triggers() {
-        assert!((trigger_to_nt(
+        let mut graph = Graph::new();
+        trigger_to_nt(
             &MultiProgress::new(),
-            Arc::new(RustemonClient::default()),
-            mpsc::unbounded_channel().0
+            fixtures::client(),
+            &mut graph,
+            &Failures::default(),
         )
-        .await)
-            .is_ok())
+        .await
+        .unwrap();
+        fixtures::assert_golden("triggers", &graph);
     }
 }
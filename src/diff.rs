@@ -0,0 +1,518 @@
+use oxrdf::vocab::rdf;
+use oxrdf::{
+    BlankNode, BlankNodeRef, Graph, NamedOrBlankNode, NamedOrBlankNodeRef, Term, TermRef, Triple,
+    TripleRef,
+};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{BufWriter, Write};
+
+use crate::read_graph;
+use crate::Error;
+
+/// How added and removed triples are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PatchFormat {
+    /// [RDF Patch](https://afs.github.io/rdf-patch/): one transaction of `D` and `A` rows.
+    #[default]
+    RdfPatch,
+    /// A SPARQL 1.1 Update script. Removed blank nodes become variables of a `DELETE WHERE`.
+    SparqlUpdate,
+}
+
+/// Entities of one class that differ between two dumps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ClassChanges {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+}
+
+/// Triples only in the new dump and only in the old one. Blank nodes of both dumps are relabelled
+/// by [`canonicalize`], so the same structure compares equal whatever label a run gave it.
+#[derive(Debug, Default)]
+pub struct Diff {
+    pub added: Graph,
+    pub removed: Graph,
+    old: Graph,
+    new: Graph,
+}
+
+/// Reads two dumps in any format [`read_graph`] supports, compares them and writes the patch to
+/// `output` if given.
+pub fn diff_files(
+    old: &str,
+    new: &str,
+    output: Option<&str>,
+    format: PatchFormat,
+) -> Result<Diff, Error> {
+    let diff = diff(&read_graph(old)?, &read_graph(new)?)?;
+    if let Some(output) = output {
+        let output_file = std::fs::File::create(output)
+            .map_err(|e| Error::io(format!("Failed to create output file {}", output), e))?;
+        diff.write_patch(format, BufWriter::new(output_file))?;
+    }
+    Ok(diff)
+}
+
+/// Compares two dumps after relabelling their blank nodes.
+pub fn diff(old: &Graph, new: &Graph) -> Result<Diff, Error> {
+    let old = canonicalize(old)?;
+    let new = canonicalize(new)?;
+    let mut added = Graph::new();
+    let mut removed = Graph::new();
+    for t in new.iter() {
+        if !old.contains(t) {
+            added.insert(t);
+        }
+    }
+    for t in old.iter() {
+        if !new.contains(t) {
+            removed.insert(t);
+        }
+    }
+    Ok(Diff {
+        added,
+        removed,
+        old,
+        new,
+    })
+}
+
+/// Relabels every blank node with a hash of what it describes and where it hangs: its outgoing
+/// triples (nested blank nodes by their content) and the subjects and predicates pointing at it.
+/// The label of a blank node only depends on its own neighbourhood, so an unchanged Pokémon stat
+/// keeps its label when anything else in the dump changes. Blank nodes with the same neighbourhood,
+/// like two identical stats of one Pokémon, are told apart by a counter.
+pub fn canonicalize(graph: &Graph) -> Result<Graph, Error> {
+    let mut labeler = Labeler {
+        graph,
+        contents: HashMap::new(),
+        labels: HashMap::new(),
+        finals: HashMap::new(),
+        taken: HashSet::new(),
+        ties: HashMap::new(),
+        visiting: HashSet::new(),
+        labelling: HashSet::new(),
+        finalizing: HashSet::new(),
+    };
+    let mut canonical = Graph::new();
+    for t in graph.iter() {
+        let subject: NamedOrBlankNode = match t.subject {
+            NamedOrBlankNodeRef::BlankNode(b) => labeler.node(b)?.into(),
+            s => s.into_owned(),
+        };
+        let object: Term = match t.object {
+            TermRef::BlankNode(b) => labeler.node(b)?.into(),
+            o => o.into_owned(),
+        };
+        canonical.insert(&Triple::new(subject, t.predicate, object));
+    }
+    Ok(canonical)
+}
+
+struct Labeler<'a> {
+    graph: &'a Graph,
+    contents: HashMap<BlankNodeRef<'a>, String>,
+    labels: HashMap<BlankNodeRef<'a>, String>,
+    finals: HashMap<BlankNodeRef<'a>, String>,
+    taken: HashSet<String>,
+    // how many blank nodes with a taken label got one so far, by signature
+    ties: HashMap<String, usize>,
+    // guard against cycles of blank nodes
+    visiting: HashSet<BlankNodeRef<'a>>,
+    labelling: HashSet<BlankNodeRef<'a>>,
+    finalizing: HashSet<BlankNodeRef<'a>>,
+}
+
+// stands for a blank node already on the path, in place of its hash
+const CYCLE: &str = "cycle";
+
+impl<'a> Labeler<'a> {
+    fn node(&mut self, node: BlankNodeRef<'a>) -> Result<BlankNode, Error> {
+        let label = self.final_label(node);
+        Ok(BlankNode::new(format!("c{}", &label[..32]))?)
+    }
+
+    // hash of the outgoing triples, nested blank nodes by their own content
+    fn content(&mut self, node: BlankNodeRef<'a>) -> String {
+        if let Some(content) = self.contents.get(&node) {
+            return content.clone();
+        }
+        if !self.visiting.insert(node) {
+            return CYCLE.to_string();
+        }
+        let mut lines = vec![];
+        for t in self.graph.triples_for_subject(node) {
+            let object = match t.object {
+                TermRef::BlankNode(b) => format!("_:{}", self.content(b)),
+                o => o.to_string(),
+            };
+            lines.push(format!("{} {}", t.predicate, object));
+        }
+        self.visiting.remove(&node);
+        let content = hash(lines);
+        self.contents.insert(node, content.clone());
+        content
+    }
+
+    // hash of the content and of the subjects and predicates pointing at the node
+    fn label(&mut self, node: BlankNodeRef<'a>) -> String {
+        if let Some(label) = self.labels.get(&node) {
+            return label.clone();
+        }
+        let mut lines = vec![self.content(node)];
+        if !self.labelling.insert(node) {
+            return CYCLE.to_string();
+        }
+        for t in self.graph.triples_for_object(node) {
+            let subject = match t.subject {
+                NamedOrBlankNodeRef::BlankNode(b) => format!("_:{}", self.label(b)),
+                s => s.to_string(),
+            };
+            lines.push(format!("{} {}", subject, t.predicate));
+        }
+        self.labelling.remove(&node);
+        let label = hash(lines);
+        self.labels.insert(node, label.clone());
+        label
+    }
+
+    // the label made unique: a node whose label is taken already gets a hash of its label, its
+    // parents' final labels and a counter, so children of identical nodes follow their parent
+    fn final_label(&mut self, node: BlankNodeRef<'a>) -> String {
+        if let Some(label) = self.finals.get(&node) {
+            return label.clone();
+        }
+        let mut lines = vec![self.label(node)];
+        if !self.finalizing.insert(node) {
+            return CYCLE.to_string();
+        }
+        for t in self.graph.triples_for_object(node) {
+            if let NamedOrBlankNodeRef::BlankNode(b) = t.subject {
+                lines.push(format!("_:{} {}", self.final_label(b), t.predicate));
+            }
+        }
+        self.finalizing.remove(&node);
+        let mut label = lines[0].clone();
+        if !self.taken.insert(label.clone()) {
+            let signature = hash(lines);
+            let tie = self.ties.entry(signature.clone()).or_default();
+            *tie += 1;
+            label = hash(vec![signature, tie.to_string()]);
+            self.taken.insert(label.clone());
+        }
+        self.finals.insert(node, label.clone());
+        label
+    }
+}
+
+fn hash(mut lines: Vec<String>) -> String {
+    lines.sort();
+    let mut hasher = Sha256::new();
+    for line in lines {
+        hasher.update(line.as_bytes());
+        hasher.update(b"\n");
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    /// Writes the removed and added triples as a patch that turns the old dump into the new one.
+    pub fn write_patch(&self, format: PatchFormat, mut writer: impl Write) -> Result<(), Error> {
+        match format {
+            PatchFormat::RdfPatch => {
+                writeln!(writer, "TX .")?;
+                for t in sorted(&self.removed) {
+                    writeln!(writer, "D {t} .")?;
+                }
+                for t in sorted(&self.added) {
+                    writeln!(writer, "A {t} .")?;
+                }
+                writeln!(writer, "TC .")?;
+            }
+            PatchFormat::SparqlUpdate => {
+                // DELETE DATA takes no blank nodes, they are matched as variables instead, one
+                // DELETE WHERE per group of connected blank nodes
+                let (ground, groups) = blank_node_groups(&self.removed);
+                if !ground.is_empty() {
+                    writeln!(writer, "DELETE DATA {{")?;
+                    for t in &ground {
+                        writeln!(writer, "  {t} .")?;
+                    }
+                    writeln!(writer, "}};")?;
+                }
+                for group in groups {
+                    writeln!(writer, "DELETE WHERE {{")?;
+                    for t in group {
+                        writeln!(writer, "  {} .", as_pattern(t))?;
+                    }
+                    writeln!(writer, "}};")?;
+                }
+                if !self.added.is_empty() {
+                    writeln!(writer, "INSERT DATA {{")?;
+                    for t in sorted(&self.added) {
+                        writeln!(writer, "  {t} .")?;
+                    }
+                    writeln!(writer, "}};")?;
+                }
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Counts the entities that were added, removed or changed, by the local name of their
+    /// `rdf:type`. Changes to blank nodes are counted for the named resource they belong to.
+    pub fn summary(&self) -> BTreeMap<String, ClassChanges> {
+        let mut roots: HashSet<NamedOrBlankNodeRef> = HashSet::new();
+        for t in self.removed.iter() {
+            roots.insert(root(&self.old, t.subject));
+        }
+        for t in self.added.iter() {
+            roots.insert(root(&self.new, t.subject));
+        }
+        let mut summary: BTreeMap<String, ClassChanges> = BTreeMap::new();
+        for entity in roots {
+            let in_old = self.old.triples_for_subject(entity).next().is_some();
+            let in_new = self.new.triples_for_subject(entity).next().is_some();
+            let class = class(&self.new, entity)
+                .or_else(|| class(&self.old, entity))
+                .unwrap_or_else(|| "untyped".to_string());
+            let changes = summary.entry(class).or_default();
+            match (in_old, in_new) {
+                (true, true) => changes.changed += 1,
+                (false, _) => changes.added += 1,
+                (true, false) => changes.removed += 1,
+            }
+        }
+        summary
+    }
+}
+
+// The named resource a blank node hangs from, or the node itself
+fn root<'a>(graph: &'a Graph, node: NamedOrBlankNodeRef<'a>) -> NamedOrBlankNodeRef<'a> {
+    let mut node = node;
+    let mut seen = HashSet::new();
+    while let NamedOrBlankNodeRef::BlankNode(b) = node {
+        if !seen.insert(b) {
+            break;
+        }
+        match graph.triples_for_object(b).next() {
+            Some(parent) => node = parent.subject,
+            None => break,
+        }
+    }
+    node
+}
+
+fn class(graph: &Graph, entity: NamedOrBlankNodeRef) -> Option<String> {
+    match graph.object_for_subject_predicate(entity, rdf::TYPE)? {
+        TermRef::NamedNode(class) => {
+            let iri = class.as_str();
+            Some(iri[iri.rfind(['#', '/']).map_or(0, |i| i + 1)..].to_string())
+        }
+        _ => None,
+    }
+}
+
+fn sorted(graph: &Graph) -> Vec<String> {
+    let mut lines: Vec<String> = graph.iter().map(|t| t.to_string()).collect();
+    lines.sort();
+    lines
+}
+
+// Triples without blank nodes, and the others grouped by the blank nodes they share
+fn blank_node_groups(graph: &Graph) -> (Vec<String>, Vec<Vec<TripleRef<'_>>>) {
+    let mut ground = vec![];
+    let mut parent: HashMap<BlankNodeRef, BlankNodeRef> = HashMap::new();
+    fn find<'a>(
+        parent: &HashMap<BlankNodeRef<'a>, BlankNodeRef<'a>>,
+        b: BlankNodeRef<'a>,
+    ) -> BlankNodeRef<'a> {
+        let mut b = b;
+        while let Some(p) = parent.get(&b).filter(|p| **p != b) {
+            b = *p;
+        }
+        b
+    }
+    let mut with_blank_nodes = vec![];
+    for t in graph.iter() {
+        let nodes: Vec<BlankNodeRef> = [blank(t.subject.into()), blank(t.object)]
+            .into_iter()
+            .flatten()
+            .collect();
+        if nodes.is_empty() {
+            ground.push(t.to_string());
+            continue;
+        }
+        for b in &nodes {
+            parent.entry(*b).or_insert(*b);
+        }
+        if let [a, b] = nodes[..] {
+            let (a, b) = (find(&parent, a), find(&parent, b));
+            parent.insert(a, b);
+        }
+        with_blank_nodes.push((t, nodes[0]));
+    }
+    ground.sort();
+    let mut groups: BTreeMap<String, Vec<TripleRef>> = BTreeMap::new();
+    for (t, b) in with_blank_nodes {
+        groups
+            .entry(find(&parent, b).as_str().to_string())
+            .or_default()
+            .push(t);
+    }
+    let groups = groups
+        .into_values()
+        .map(|mut group| {
+            group.sort_by_key(|t| t.to_string());
+            group
+        })
+        .collect();
+    (ground, groups)
+}
+
+fn blank(term: TermRef) -> Option<BlankNodeRef> {
+    match term {
+        TermRef::BlankNode(b) => Some(b),
+        _ => None,
+    }
+}
+
+// A triple pattern with blank nodes written as variables
+fn as_pattern(t: TripleRef) -> String {
+    let term = |term: TermRef| match term {
+        TermRef::BlankNode(b) => format!("?{}", b.as_str()),
+        term => term.to_string(),
+    };
+    format!(
+        "{} {} {}",
+        term(t.subject.into()),
+        t.predicate,
+        term(t.object)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use oxttl::NTriplesParser;
+
+    fn parse(nt: &str) -> Graph {
+        let mut graph = Graph::new();
+        for t in NTriplesParser::new().for_slice(nt) {
+            graph.insert(&t.unwrap());
+        }
+        graph
+    }
+
+    static OLD: &str = r#"
+<https://pokeapi.co/api/v2/pokemon/1/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://pokemonkg.org/ontology#Pokemon> .
+<https://pokeapi.co/api/v2/pokemon/1/> <http://purl.org/pokemon/ontology#pokemonStat> _:a .
+_:a <http://purl.org/pokemon/ontology#stat> <https://pokeapi.co/api/v2/stat/1/> .
+_:a <http://purl.org/pokemon/ontology#baseStat> "45"^^<http://www.w3.org/2001/XMLSchema#integer> .
+<https://pokeapi.co/api/v2/pokemon/2/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://pokemonkg.org/ontology#Pokemon> .
+<https://pokeapi.co/api/v2/pokemon/2/> <http://purl.org/pokemon/ontology#pokemonStat> _:b .
+_:b <http://purl.org/pokemon/ontology#stat> <https://pokeapi.co/api/v2/stat/1/> .
+_:b <http://purl.org/pokemon/ontology#baseStat> "45"^^<http://www.w3.org/2001/XMLSchema#integer> .
+<https://pokeapi.co/api/v2/move/1/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://pokemonkg.org/ontology#Move> .
+<https://pokeapi.co/api/v2/move/1/> <https://pokemonkg.org/ontology#basePower> "40"^^<http://www.w3.org/2001/XMLSchema#integer> .
+"#;
+
+    // same data with other blank node labels, Ivysaur's HP and Pound's power changed, Mega Punch added
+    static NEW: &str = r#"
+<https://pokeapi.co/api/v2/pokemon/1/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://pokemonkg.org/ontology#Pokemon> .
+<https://pokeapi.co/api/v2/pokemon/1/> <http://purl.org/pokemon/ontology#pokemonStat> _:x .
+_:x <http://purl.org/pokemon/ontology#stat> <https://pokeapi.co/api/v2/stat/1/> .
+_:x <http://purl.org/pokemon/ontology#baseStat> "45"^^<http://www.w3.org/2001/XMLSchema#integer> .
+<https://pokeapi.co/api/v2/pokemon/2/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://pokemonkg.org/ontology#Pokemon> .
+<https://pokeapi.co/api/v2/pokemon/2/> <http://purl.org/pokemon/ontology#pokemonStat> _:y .
+_:y <http://purl.org/pokemon/ontology#stat> <https://pokeapi.co/api/v2/stat/1/> .
+_:y <http://purl.org/pokemon/ontology#baseStat> "60"^^<http://www.w3.org/2001/XMLSchema#integer> .
+<https://pokeapi.co/api/v2/move/1/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://pokemonkg.org/ontology#Move> .
+<https://pokeapi.co/api/v2/move/1/> <https://pokemonkg.org/ontology#basePower> "50"^^<http://www.w3.org/2001/XMLSchema#integer> .
+<https://pokeapi.co/api/v2/move/5/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://pokemonkg.org/ontology#Move> .
+"#;
+
+    #[test]
+    fn test_diff() {
+        let old = parse(OLD);
+        assert!(diff(&old, &parse(&OLD.replace("_:a", "_:z")))
+            .unwrap()
+            .is_empty());
+
+        let diff = diff(&old, &parse(NEW)).unwrap();
+        // Ivysaur's stat node is replaced as a whole, Bulbasaur's is untouched
+        assert_eq!(diff.removed.len(), 4);
+        assert_eq!(diff.added.len(), 5);
+        assert_eq!(
+            diff.summary(),
+            BTreeMap::from([
+                (
+                    "Move".to_string(),
+                    ClassChanges {
+                        added: 1,
+                        removed: 0,
+                        changed: 1
+                    }
+                ),
+                (
+                    "Pokemon".to_string(),
+                    ClassChanges {
+                        added: 0,
+                        removed: 0,
+                        changed: 1
+                    }
+                ),
+            ])
+        );
+
+        let mut patch = vec![];
+        diff.write_patch(PatchFormat::RdfPatch, &mut patch).unwrap();
+        let patch = String::from_utf8(patch).unwrap();
+        assert!(patch.starts_with("TX .\nD "));
+        assert!(patch.contains("A <https://pokeapi.co/api/v2/move/1/> <https://pokemonkg.org/ontology#basePower> \"50\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n"));
+        assert!(patch.ends_with("TC .\n"));
+
+        let mut update = vec![];
+        diff.write_patch(PatchFormat::SparqlUpdate, &mut update)
+            .unwrap();
+        let update = String::from_utf8(update).unwrap();
+        assert_eq!(update.matches("DELETE DATA").count(), 1);
+        assert_eq!(update.matches("DELETE WHERE").count(), 1);
+        assert!(update.contains("<https://pokeapi.co/api/v2/pokemon/2/> <http://purl.org/pokemon/ontology#pokemonStat> ?c"));
+        assert_eq!(update.matches("INSERT DATA").count(), 1);
+    }
+
+    #[test]
+    fn test_canonicalize_identical_siblings() {
+        // two stat nodes with the same content under one Pokémon, each with a nested effort node
+        let nt = r#"
+<https://pokeapi.co/api/v2/pokemon/1/> <http://purl.org/pokemon/ontology#pokemonStat> _:a .
+<https://pokeapi.co/api/v2/pokemon/1/> <http://purl.org/pokemon/ontology#pokemonStat> _:b .
+_:a <http://purl.org/pokemon/ontology#baseStat> "45"^^<http://www.w3.org/2001/XMLSchema#integer> .
+_:b <http://purl.org/pokemon/ontology#baseStat> "45"^^<http://www.w3.org/2001/XMLSchema#integer> .
+_:a <http://purl.org/pokemon/ontology#effort> _:c .
+_:b <http://purl.org/pokemon/ontology#effort> _:d .
+_:c <http://purl.org/pokemon/ontology#value> "1"^^<http://www.w3.org/2001/XMLSchema#integer> .
+_:d <http://purl.org/pokemon/ontology#value> "1"^^<http://www.w3.org/2001/XMLSchema#integer> .
+"#;
+        let graph = parse(nt);
+        let canonical = canonicalize(&graph).unwrap();
+        assert_eq!(canonical.len(), graph.len());
+        let relabelled = canonicalize(&parse(&nt.replace("_:a", "_:z"))).unwrap();
+        assert_eq!(canonical, relabelled);
+
+        // a cycle of blank nodes still gets labels
+        let cycle = parse(
+            "_:a <http://purl.org/pokemon/ontology#next> _:b .\n_:b <http://purl.org/pokemon/ontology#next> _:a .\n",
+        );
+        assert_eq!(canonicalize(&cycle).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,166 @@
+use oxrdf::{BlankNodeIdParseError, IriParseError, LanguageTagParseError};
+use tokio::sync::mpsc::error::SendError;
+
+/// Everything that can go wrong while building, projecting or mirroring the graph.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// A PokeAPI request failed, `url` is the resource or endpoint that was requested.
+    #[error("error getting {url}: {source}")]
+    Fetch {
+        url: String,
+        #[source]
+        source: reqwest_middleware::Error,
+    },
+    /// A PokeAPI response arrived but is not the JSON the resource should have.
+    #[error("error decoding {url}: {source}")]
+    Decode {
+        url: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    /// A sprite download failed.
+    #[error("error downloading {url}: {source}")]
+    Download {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    /// The HTTP client could not be set up, e.g. with an invalid User-Agent.
+    #[error("error setting up the HTTP client: {0}")]
+    Client(#[source] reqwest::Error),
+    /// A resource URL or vocabulary term is not a valid IRI.
+    #[error("invalid IRI: {0}")]
+    Iri(#[from] IriParseError),
+    /// A generated blank node id is invalid.
+    #[error("invalid blank node id: {0}")]
+    BlankNode(#[from] BlankNodeIdParseError),
+    /// A PokeAPI language name is not a valid language tag.
+    #[error("invalid language tag: {0}")]
+    LanguageTag(#[from] LanguageTagParseError),
+    /// A dump (N-Triples, N-Quads, Turtle or TriG) could not be parsed while reading it. Errors
+    /// writing output are [`Error::Io`].
+    #[error("invalid RDF: {0}")]
+    Parse(#[from] oxttl::TurtleParseError),
+    /// Reading or writing a file failed.
+    #[error("{context}: {source}")]
+    Io {
+        context: String,
+        #[source]
+        source: std::io::Error,
+    },
+    /// The writer stopped before all triples were sent.
+    #[error("output channel closed")]
+    ChannelClosed,
+    /// The writer task panicked or was cancelled.
+    #[error("writer task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+    /// The embedded store could not be opened, read or written.
+    #[error("store error: {0}")]
+    Store(#[from] oxigraph::store::StorageError),
+    /// A dump could not be loaded into a store.
+    #[error("error loading store: {0}")]
+    Load(#[from] oxigraph::store::LoaderError),
+    /// A SPARQL query is not valid.
+    #[error("invalid SPARQL query: {0}")]
+    QuerySyntax(#[from] oxigraph::sparql::SparqlSyntaxError),
+    /// A SPARQL query failed while running.
+    #[error("query failed: {0}")]
+    Query(#[from] oxigraph::sparql::QueryEvaluationError),
+    /// A build skipped more resources than allowed, nothing was published.
+    #[error("{failed} resources failed, at most {max} allowed")]
+    TooManyFailures { failed: usize, max: usize },
+    /// The data does not have the expected shape, e.g. a URL without a resource id.
+    #[error("{0}")]
+    InvalidData(String),
+}
+
+impl Error {
+    pub(crate) fn fetch(
+        url: impl Into<String>,
+        source: impl Into<reqwest_middleware::Error>,
+    ) -> Self {
+        Error::Fetch {
+            url: url.into(),
+            source: source.into(),
+        }
+    }
+
+    pub(crate) fn io(context: impl Into<String>, source: std::io::Error) -> Self {
+        Error::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    /// Whether the same call may succeed when tried again: timeouts, requests that could not be sent,
+    /// rate limiting and server errors. Invalid data, closed channels and client errors
+    /// are permanent.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Fetch { source, .. } => retryable_middleware(source),
+            Error::Download { source, .. } => retryable_request(source),
+            Error::Io { source, .. } => matches!(
+                source.kind(),
+                std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::WouldBlock
+            ),
+            _ => false,
+        }
+    }
+}
+
+fn retryable_middleware(e: &reqwest_middleware::Error) -> bool {
+    match e {
+        reqwest_middleware::Error::Reqwest(e) => retryable_request(e),
+        // the cache middleware hands request failures back as a boxed error in another layer
+        reqwest_middleware::Error::Middleware(e) => e
+            .downcast_ref::<Box<dyn std::error::Error + Send + Sync>>()
+            .and_then(|e| e.downcast_ref::<reqwest_middleware::Error>())
+            .is_some_and(retryable_middleware),
+    }
+}
+
+fn retryable_request(e: &reqwest::Error) -> bool {
+    e.is_timeout()
+        || e.is_connect()
+        || e.is_request()
+        || e.status()
+            .is_some_and(|s| s.is_server_error() || s == reqwest::StatusCode::TOO_MANY_REQUESTS)
+}
+
+impl<T> From<SendError<T>> for Error {
+    fn from(_: SendError<T>) -> Self {
+        Error::ChannelClosed
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(source: std::io::Error) -> Self {
+        Error::io("I/O error", source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_is_retryable() {
+        let iri: Error = oxrdf::NamedNode::new("not an iri").unwrap_err().into();
+        assert!(!iri.is_retryable());
+        assert!(!Error::ChannelClosed.is_retryable());
+        let timeout = Error::io("reading", std::io::ErrorKind::TimedOut.into());
+        assert!(timeout.is_retryable());
+        let missing = Error::fetch(
+            "https://pokeapi.co/api/v2/pokemon/",
+            reqwest_middleware::Error::middleware(std::io::Error::other("not found")),
+        );
+        assert!(!missing.is_retryable());
+        assert_eq!(
+            missing.to_string(),
+            "error getting https://pokeapi.co/api/v2/pokemon/: not found"
+        );
+    }
+}
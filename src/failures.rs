@@ -0,0 +1,142 @@
+use serde::Serialize;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::Error;
+
+/// What happens when a single resource cannot be fetched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FailureMode {
+    /// Stop the build at the first failure.
+    #[default]
+    Abort,
+    /// Record the failure, skip the resource and carry on with the rest of the collection.
+    Skip,
+}
+
+/// A resource that was skipped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Failure {
+    /// Collection the resource belongs to, e.g. `pokemon-species`.
+    pub collection: String,
+    /// Resource or endpoint URL that failed, empty for errors that are not about a request.
+    pub url: String,
+    pub error: String,
+    pub retryable: bool,
+}
+
+/// Collects skipped resources while converters run, or hands the error back when aborting.
+#[derive(Debug, Default)]
+pub struct Failures {
+    mode: FailureMode,
+    recorded: Mutex<Vec<Failure>>,
+}
+
+impl Failures {
+    pub fn new(mode: FailureMode) -> Self {
+        Failures {
+            mode,
+            recorded: Mutex::new(vec![]),
+        }
+    }
+
+    // Err in abort mode, so callers can `?` it and skip the resource otherwise
+    pub(crate) fn record(&self, collection: &str, error: Error) -> Result<(), Error> {
+        if self.mode == FailureMode::Abort {
+            return Err(error);
+        }
+        let url = match &error {
+            Error::Fetch { url, .. } | Error::Decode { url, .. } | Error::Download { url, .. } => {
+                url.clone()
+            }
+            _ => String::new(),
+        };
+        self.recorded.lock().unwrap().push(Failure {
+            collection: collection.to_string(),
+            url,
+            error: error.to_string(),
+            retryable: error.is_retryable(),
+        });
+        Ok(())
+    }
+
+    // Records the error of a resource that could not be fetched or decoded like `record`, any
+    // other error ends the conversion
+    pub(crate) fn skip_failed(
+        &self,
+        collection: &str,
+        result: Result<(), Error>,
+    ) -> Result<(), Error> {
+        match result {
+            Err(e @ (Error::Fetch { .. } | Error::Decode { .. })) => self.record(collection, e),
+            result => result,
+        }
+    }
+
+    /// The resources that were skipped.
+    pub fn into_inner(self) -> Vec<Failure> {
+        self.recorded.into_inner().unwrap()
+    }
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    total: usize,
+    failures: &'a [Failure],
+}
+
+/// Writes the skipped resources as JSON: `{"total": n, "failures": [...]}`.
+pub fn write_report(path: &Path, failures: &[Failure]) -> Result<(), Error> {
+    let file = std::fs::File::create(path).map_err(|e| {
+        Error::io(
+            format!("Failed to create report file {}", path.display()),
+            e,
+        )
+    })?;
+    let mut writer = BufWriter::new(file);
+    let report = Report {
+        total: failures.len(),
+        failures,
+    };
+    serde_json::to_writer_pretty(&mut writer, &report).map_err(|e| {
+        Error::io(
+            format!("Failed to write report file {}", path.display()),
+            e.into(),
+        )
+    })?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let error = || {
+            Error::fetch(
+                "https://pokeapi.co/api/v2/pokemon-species/10001/",
+                reqwest_middleware::Error::middleware(std::io::Error::other("not found")),
+            )
+        };
+        assert!(Failures::new(FailureMode::Abort)
+            .record("pokemon-species", error())
+            .is_err());
+
+        let failures = Failures::new(FailureMode::Skip);
+        assert!(failures.record("pokemon-species", error()).is_ok());
+        assert_eq!(
+            failures.into_inner(),
+            vec![Failure {
+                collection: "pokemon-species".to_string(),
+                url: "https://pokeapi.co/api/v2/pokemon-species/10001/".to_string(),
+                error: "error getting https://pokeapi.co/api/v2/pokemon-species/10001/: not found"
+                    .to_string(),
+                retryable: false,
+            }]
+        );
+    }
+}
@@ -0,0 +1,506 @@
+use oxrdf::Triple;
+use oxttl::NTriplesParser;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::failures::Failure;
+use crate::output::AtomicFile;
+use crate::sink::TripleSink;
+use crate::Error;
+
+/// File that remembers, per resource URL, the hashes of the last build.
+pub static STATE: &str = "pokemon-state.tsv";
+
+// sha256 of the JSON a resource was built from (empty for derived sections like type charts)
+// and of the N-Triples lines emitted for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Hashes {
+    json: String,
+    triples: String,
+}
+
+// What the last incremental build produced
+#[derive(Debug, Default)]
+struct State {
+    dump: Option<String>,
+    resources: HashMap<String, Hashes>,
+}
+
+impl State {
+    fn read(path: &Path) -> Result<Self, Error> {
+        let mut state = State::default();
+        if !path.exists() {
+            return Ok(state);
+        }
+        let file = std::fs::File::open(path)
+            .map_err(|e| Error::io(format!("Failed to open {}", path.display()), e))?;
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if let Some(dump) = line.strip_prefix("# dump\t") {
+                state.dump = Some(dump.to_string());
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [url, json, triples] = fields[..] else {
+                continue;
+            };
+            state.resources.insert(
+                url.to_string(),
+                Hashes {
+                    json: json.to_string(),
+                    triples: triples.to_string(),
+                },
+            );
+        }
+        Ok(state)
+    }
+}
+
+// The resource whose lines are currently coming in
+#[derive(Debug)]
+struct Section {
+    url: String,
+    // sha256 of the JSON it was built from, empty for derived sections
+    json: String,
+    lines: Vec<String>,
+}
+
+// The dump of the last incremental build and where each resource's lines are in it
+#[derive(Debug, Default)]
+struct PreviousDump {
+    file: Option<File>,
+    // offset and length of the lines after the `# <url>` comment
+    sections: HashMap<String, (u64, usize)>,
+}
+
+impl PreviousDump {
+    // Without the dump nothing can be reused, every resource is converted again
+    fn open(path: Option<&str>) -> Result<Self, Error> {
+        let Some(Ok(file)) = path.map(File::open) else {
+            return Ok(PreviousDump::default());
+        };
+        let mut sections = HashMap::new();
+        let mut current: Option<(String, u64)> = None;
+        let mut offset = 0;
+        let mut reader = BufReader::new(&file);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            if read == 0 || line.starts_with("# ") {
+                if let Some((url, start)) = current.take() {
+                    sections.insert(url, (start, (offset - start) as usize));
+                }
+            }
+            if read == 0 {
+                break;
+            }
+            offset += read as u64;
+            if let Some(url) = line.strip_prefix("# ") {
+                current = Some((url.trim_end().to_string(), offset));
+            }
+        }
+        Ok(PreviousDump {
+            file: Some(file),
+            sections,
+        })
+    }
+
+    // The N-Triples lines of a resource
+    fn lines(&mut self, url: &str) -> Result<Option<Vec<String>>, Error> {
+        let (Some(file), Some(&(offset, len))) = (&mut self.file, self.sections.get(url)) else {
+            return Ok(None);
+        };
+        let mut buf = vec![0; len];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut buf)?;
+        let text = String::from_utf8(buf)
+            .map_err(|e| Error::InvalidData(format!("previous dump is not UTF-8: {e}")))?;
+        Ok(Some(text.lines().map(str::to_string).collect()))
+    }
+}
+
+/// Numbers reported at the end of an incremental build.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Changes {
+    /// Resources that are new or whose triples changed, written to the delta file.
+    pub changed: usize,
+    pub unchanged: usize,
+    /// Unchanged resources whose JSON was the same, their triples were taken from the previous
+    /// dump without converting them.
+    pub reused: usize,
+    /// Resources that failed this time and were copied over from the previous dump.
+    pub kept: usize,
+    /// Resources of the previous dump that PokeAPI no longer lists.
+    pub removed: usize,
+}
+
+// Follows the lines sent to the dump, splits them into resources at the `# <url>` comments and
+// compares each resource with the previous build. Changed resources go to the delta file.
+// Resources whose JSON has the hash of the previous build are taken from the previous dump.
+#[derive(Debug)]
+pub(crate) struct Tracker {
+    state_path: PathBuf,
+    previous: State,
+    previous_dump: PreviousDump,
+    current: BTreeMap<String, Hashes>,
+    // JSON hash of the resource about to be converted
+    json: Option<(String, String)>,
+    section: Option<Section>,
+    delta: BufWriter<AtomicFile>,
+    changes: Changes,
+}
+
+impl Tracker {
+    pub(crate) fn open(state: &Path, delta: &Path) -> Result<Self, Error> {
+        let previous = State::read(state)?;
+        Ok(Tracker {
+            state_path: state.to_path_buf(),
+            previous_dump: PreviousDump::open(previous.dump.as_deref())?,
+            previous,
+            current: BTreeMap::new(),
+            json: None,
+            section: None,
+            delta: BufWriter::new(AtomicFile::create(delta)?),
+            changes: Changes::default(),
+        })
+    }
+
+    pub(crate) fn line(&mut self, line: &str) -> Result<(), Error> {
+        if let Some(url) = line.strip_prefix("# ") {
+            self.end_section()?;
+            let json = match self.json.take() {
+                Some((json_url, hash)) if json_url == url => hash,
+                _ => String::new(),
+            };
+            self.section = Some(Section {
+                url: url.to_string(),
+                json,
+                lines: vec![],
+            });
+        } else if let Some(section) = &mut self.section {
+            section.lines.push(line.to_string());
+        }
+        Ok(())
+    }
+
+    fn end_section(&mut self) -> Result<(), Error> {
+        let Some(section) = self.section.take() else {
+            return Ok(());
+        };
+        let mut hasher = Sha256::new();
+        for line in &section.lines {
+            hasher.update(line.as_bytes());
+            hasher.update(b"\n");
+        }
+        let hashes = Hashes {
+            json: section.json,
+            triples: format!("{:x}", hasher.finalize()),
+        };
+        let changed = self
+            .previous
+            .resources
+            .get(&section.url)
+            .is_none_or(|previous| previous.triples != hashes.triples);
+        if changed {
+            writeln!(self.delta, "# {}", section.url)?;
+            for line in &section.lines {
+                writeln!(self.delta, "{}", line)?;
+            }
+            self.changes.changed += 1;
+        } else {
+            self.changes.unchanged += 1;
+        }
+        self.current.insert(section.url, hashes);
+        Ok(())
+    }
+
+    // Copies resources that could not be fetched this time from the previous dump into `output`,
+    // so a skipped resource keeps its last known triples. Everything else the previous build had
+    // is gone upstream and listed as removed in the delta file.
+    pub(crate) fn finish(
+        &mut self,
+        output: &mut impl Write,
+        failures: &[Failure],
+    ) -> Result<(), Error> {
+        self.end_section()?;
+        // a failed list page keeps the whole collection, a failed resource just itself
+        let failed: Vec<&str> = failures
+            .iter()
+            .filter(|f| !f.url.is_empty())
+            .map(|f| f.url.split('?').next().unwrap_or_default())
+            .collect();
+        let mut kept = HashSet::new();
+        let mut removed = vec![];
+        for url in self.previous.resources.keys() {
+            if self.current.contains_key(url) {
+                continue;
+            }
+            if failed.iter().any(|f| url.starts_with(f)) {
+                kept.insert(url.as_str());
+            } else {
+                removed.push(url.as_str());
+            }
+        }
+
+        if !kept.is_empty() {
+            let dump = self.previous.dump.as_deref().unwrap_or_default();
+            match std::fs::File::open(dump) {
+                Ok(file) => {
+                    let mut copying = false;
+                    for line in BufReader::new(file).lines() {
+                        let line = line?;
+                        if let Some(url) = line.strip_prefix("# ") {
+                            copying = kept.contains(url);
+                            if copying {
+                                self.current
+                                    .insert(url.to_string(), self.previous.resources[url].clone());
+                                self.changes.kept += 1;
+                            }
+                        }
+                        if copying {
+                            writeln!(output, "{}", line)?;
+                        }
+                    }
+                }
+                // without the previous dump they are simply missing and come back as changed
+                Err(e) => println!("Cannot keep skipped resources from {}: {}", dump, e),
+            }
+        }
+
+        removed.sort();
+        for url in &removed {
+            writeln!(self.delta, "# removed {}", url)?;
+        }
+        self.changes.removed = removed.len();
+        Ok(())
+    }
+
+    // Moves the delta file into place and remembers the hashes for the next build, once the
+    // new dump itself is in place
+    pub(crate) fn commit(self, dump: &str) -> Result<Changes, Error> {
+        self.delta
+            .into_inner()
+            .map_err(|e| Error::io("Error flushing delta", e.into_error()))?
+            .commit()?;
+        let mut state = BufWriter::new(AtomicFile::create(&self.state_path)?);
+        writeln!(state, "# dump\t{}", dump)?;
+        for (url, hashes) in &self.current {
+            writeln!(state, "{}\t{}\t{}", url, hashes.json, hashes.triples)?;
+        }
+        state
+            .into_inner()
+            .map_err(|e| Error::io("Error flushing state", e.into_error()))?
+            .commit()?;
+        Ok(self.changes)
+    }
+}
+
+// Follows the dump as it is written, resources and triples as the lines they are written as
+impl TripleSink for Tracker {
+    fn start_resource(&mut self, url: &str) -> Result<(), Error> {
+        self.line(&format!("# {url}"))
+    }
+
+    fn reuse(&mut self, url: &str, json_hash: &str) -> Result<Option<Vec<Triple>>, Error> {
+        self.json = Some((url.to_string(), json_hash.to_string()));
+        let unchanged = self
+            .previous
+            .resources
+            .get(url)
+            .is_some_and(|previous| previous.json == json_hash);
+        if !unchanged {
+            return Ok(None);
+        }
+        let Some(lines) = self.previous_dump.lines(url)? else {
+            return Ok(None);
+        };
+        let mut triples = vec![];
+        for line in lines {
+            for triple in NTriplesParser::new().for_slice(&line) {
+                triples.push(triple.map_err(oxttl::TurtleParseError::from)?);
+            }
+        }
+        self.changes.reused += 1;
+        Ok(Some(triples))
+    }
+
+    fn triple(&mut self, triple: Triple) -> Result<(), Error> {
+        self.line(&format!("{triple} ."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::sink::Tee;
+
+    // Runs one build over `sections`, returns the new dump, the delta and the changes
+    fn build(
+        dir: &Path,
+        name: &str,
+        sections: &[(&str, &[&str])],
+        failures: &[Failure],
+    ) -> (String, String, Changes) {
+        let dump = dir.join(format!("{name}.nt"));
+        let delta = dir.join(format!("{name}-delta.nt"));
+        let mut tracker = Tracker::open(&dir.join("state.tsv"), &delta).unwrap();
+        let mut output = vec![];
+        for (url, lines) in sections {
+            let marker = format!("# {url}");
+            writeln!(output, "{}", marker).unwrap();
+            tracker.line(&marker).unwrap();
+            for line in *lines {
+                writeln!(output, "{}", line).unwrap();
+                tracker.line(line).unwrap();
+            }
+        }
+        tracker.finish(&mut output, failures).unwrap();
+        std::fs::write(&dump, &output).unwrap();
+        let changes = tracker.commit(dump.to_str().unwrap()).unwrap();
+        (
+            String::from_utf8(output).unwrap(),
+            std::fs::read_to_string(delta).unwrap(),
+            changes,
+        )
+    }
+
+    #[test]
+    fn test_incremental() {
+        let dir =
+            std::env::temp_dir().join(format!("pokemon2rdf-incremental-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let bulbasaur = "https://pokeapi.co/api/v2/pokemon/1/";
+        let ivysaur = "https://pokeapi.co/api/v2/pokemon/2/";
+        let overgrow = "https://pokeapi.co/api/v2/ability/65/";
+        let chlorophyll = "https://pokeapi.co/api/v2/ability/34/";
+
+        let (_, delta, changes) = build(
+            &dir,
+            "first",
+            &[
+                (bulbasaur, &[&format!("<{bulbasaur}> <b> \"1\" .")]),
+                (ivysaur, &[&format!("<{ivysaur}> <b> \"2\" .")]),
+                (overgrow, &[&format!("<{overgrow}> <b> \"65\" .")]),
+            ],
+            &[],
+        );
+        assert_eq!(delta.lines().count(), 6);
+        assert_eq!(
+            changes,
+            Changes {
+                changed: 3,
+                ..Changes::default()
+            }
+        );
+
+        // bulbasaur changes, ivysaur fails to fetch, overgrow is gone and chlorophyll is new
+        let (dump, delta, changes) = build(
+            &dir,
+            "second",
+            &[
+                (bulbasaur, &[&format!("<{bulbasaur}> <b> \"one\" .")]),
+                (chlorophyll, &[&format!("<{chlorophyll}> <b> \"34\" .")]),
+            ],
+            &[Failure {
+                collection: "pokemon".to_string(),
+                url: ivysaur.to_string(),
+                error: "error getting".to_string(),
+                retryable: true,
+            }],
+        );
+        assert_eq!(
+            changes,
+            Changes {
+                changed: 2,
+                unchanged: 0,
+                reused: 0,
+                kept: 1,
+                removed: 1,
+            }
+        );
+        assert!(dump.contains(&format!("<{ivysaur}> <b> \"2\" .")));
+        assert!(!dump.contains(overgrow));
+        assert_eq!(
+            delta,
+            format!(
+                "# {bulbasaur}\n<{bulbasaur}> <b> \"one\" .\n# {chlorophyll}\n<{chlorophyll}> <b> \"34\" .\n# removed {overgrow}\n"
+            )
+        );
+
+        // nothing changed
+        let (_, delta, changes) = build(
+            &dir,
+            "third",
+            &[
+                (bulbasaur, &[&format!("<{bulbasaur}> <b> \"one\" .")]),
+                (ivysaur, &[&format!("<{ivysaur}> <b> \"2\" .")]),
+                (chlorophyll, &[&format!("<{chlorophyll}> <b> \"34\" .")]),
+            ],
+            &[],
+        );
+        assert_eq!(delta, "");
+        assert_eq!(changes.unchanged, 3);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // Resources built from the same JSON come back from the previous dump
+    #[test]
+    fn test_reuse() {
+        let dir = std::env::temp_dir().join(format!("pokemon2rdf-reuse-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let bulbasaur = "https://pokeapi.co/api/v2/pokemon/1/";
+        let ivysaur = "https://pokeapi.co/api/v2/pokemon/2/";
+        let triple = |url: &str, value: &str| {
+            Triple::new(
+                oxrdf::NamedNode::new(url).unwrap(),
+                oxrdf::NamedNode::new("https://schema.org/name").unwrap(),
+                oxrdf::Literal::new_simple_literal(value),
+            )
+        };
+        let build = |name: &str, resources: &[(&str, &str, &str)]| {
+            let dump = dir.join(format!("{name}.nt"));
+            let tracker = Tracker::open(&dir.join("state.tsv"), &dir.join("delta.nt")).unwrap();
+            let mut sink = Tee(
+                crate::sink::NTriplesSink::new(vec![]).with_comments(),
+                tracker,
+            );
+            let mut reused: Vec<String> = vec![];
+            for (url, json, value) in resources {
+                let triples = match sink.reuse(url, json).unwrap() {
+                    Some(triples) => {
+                        reused.push(url.to_string());
+                        triples
+                    }
+                    None => vec![triple(url, value)],
+                };
+                crate::collections::send_resource(&mut sink, url, triples).unwrap();
+            }
+            let Tee(output, mut tracker) = sink;
+            let mut output = output.into_inner();
+            tracker.finish(&mut output, &[]).unwrap();
+            std::fs::write(&dump, &output).unwrap();
+            let changes = tracker.commit(dump.to_str().unwrap()).unwrap();
+            (String::from_utf8(output).unwrap(), reused, changes)
+        };
+
+        let (first, reused, _) = build(
+            "first",
+            &[(bulbasaur, "a", "bulbasaur"), (ivysaur, "b", "ivysaur")],
+        );
+        assert!(reused.is_empty());
+        // the value would differ if bulbasaur were converted again
+        let (second, reused, changes) = build(
+            "second",
+            &[(bulbasaur, "a", "changed"), (ivysaur, "c", "ivysaur")],
+        );
+        assert_eq!(reused, [bulbasaur]);
+        assert_eq!(second, first);
+        assert_eq!((changes.unchanged, changes.reused), (2, 1));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
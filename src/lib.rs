@@ -1,13 +1,41 @@
-use indicatif::{MultiProgress, ProgressStyle};
+use indicatif::ProgressStyle;
 use oxrdf::vocab;
-use oxrdf::{NamedNode, Triple};
-use std::error::Error;
-use std::io::{BufWriter, Write};
+use oxrdf::{BlankNode, Graph, Literal, NamedNode, NamedOrBlankNodeRef, Triple, TripleRef};
+use oxttl::{NQuadsParser, NTriplesParser, TriGParser, TurtleParser};
+use rustemon::model::resource::NamedApiResource;
+use rustemon::model::utility::Language;
+use sha2::{Digest, Sha256};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::mpsc;
 
-pub(crate) mod collections;
-use crate::collections::*;
+pub mod assets;
+pub mod builder;
+pub mod client;
+pub mod collections;
+pub mod diff;
+pub mod error;
+pub mod failures;
+pub mod incremental;
+#[cfg(test)]
+mod mock;
+pub(crate) mod output;
+pub mod serve;
+pub mod sink;
+pub mod snapshot;
+pub mod store;
+pub use crate::builder::Builder;
+pub use crate::client::{Client, ClientConfig};
+pub use crate::collections::Collection;
+pub use crate::error::Error;
+use crate::failures::write_report;
+pub use crate::failures::{Failure, FailureMode};
+pub use crate::incremental::Changes;
+use crate::incremental::Tracker;
+use crate::output::{update_symlink, AtomicFile};
+pub use crate::sink::TripleSink;
+use crate::sink::{NTriplesSink, Tee};
+use crate::store::{check_store_support, load_store};
 
 // Pokemon ontology vocabulary namespace
 static POKE: &str = "http://purl.org/pokemon/ontology#";
@@ -15,6 +43,12 @@ static POKE: &str = "http://purl.org/pokemon/ontology#";
 // Standard vocabulary namespaces for alignment
 static POKEMONKG: &str = "https://pokemonkg.org/ontology#";
 static SCHEMA: &str = "https://schema.org/";
+
+// Prefixes declared when writing Turtle
+static PREFIXES: [(&str, &str); 3] = [("poke", POKE), ("pokemonkg", POKEMONKG), ("schema", SCHEMA)];
+
+// PokeAPI resource base, used where the API hands out bare ids instead of resource URLs
+static POKEAPI: &str = "https://pokeapi.co/api/v2/";
 // Reserved for future use:
 // static FOAF: &'static str = "http://xmlns.com/foaf/0.1/";
 // static DCTERMS: &'static str = "http://purl.org/dc/terms/";
@@ -22,158 +56,189 @@ static SCHEMA: &str = "https://schema.org/";
 // TODO can we add any of this to enhance the triples being built?
 // example: https://github.com/MarErius/Pokeapp/blob/main/MAINPROGRAM.py
 
-pub async fn build_graph() -> Result<(), Box<dyn Error + Send + Sync>> {
-    let client = rustemon::client::RustemonClient::default();
+pub async fn build_graph() -> Result<(), Error> {
+    build_graph_with(BuildOptions::default()).await.map(|_| ())
+}
+
+/// Settings of a build, the default matches [`build_graph`].
+#[derive(Debug, Clone, Default)]
+pub struct BuildOptions {
+    /// With [`FailureMode::Skip`] resources that cannot be fetched are left out instead of
+    /// aborting the build; they are returned and written to `pokemon-YYYY-MM-DD-failures.json`.
+    pub mode: FailureMode,
+    /// With [`FailureMode::Skip`], fail with [`Error::TooManyFailures`] when more resources than
+    /// this were skipped. The failure report is still written, but the dump, store and
+    /// incremental state are left as they were.
+    pub max_failures: Option<usize>,
+    /// Request rate, retries, User-Agent and base URL used for PokeAPI.
+    pub client: ClientConfig,
+    /// Keep the hashes of every resource in [`incremental::STATE`]. Resources whose triples
+    /// changed since the last incremental build are also written to `pokemon-YYYY-MM-DD-delta.nt`,
+    /// and skipped resources keep their triples from the previous dump.
+    pub incremental: bool,
+    /// Also load the graph into the embedded on-disk store at this path, replacing its content.
+    /// Needs the `rocksdb` feature, see [`check_store_support`].
+    pub store: Option<PathBuf>,
+    /// Only fill `store`, without keeping `pokemon-YYYY-MM-DD.nt`.
+    pub store_only: bool,
+    /// Directory the dump, reports and incremental state are written to, the current directory
+    /// when empty.
+    pub output_dir: PathBuf,
+}
+
+/// Builds the graph like [`build_graph`] with the given options and returns the skipped resources.
+/// Runs a [`Builder`] over every collection with progress bars, writes the dump and prints where
+/// the output went.
+pub async fn build_graph_with(options: BuildOptions) -> Result<Vec<Failure>, Error> {
+    let BuildOptions {
+        mode,
+        max_failures,
+        client: client_config,
+        incremental,
+        store,
+        store_only,
+        output_dir,
+    } = options;
+    if store_only && (store.is_none() || incremental) {
+        return Err(Error::InvalidData(
+            "store_only needs a store and cannot be combined with incremental builds".to_string(),
+        ));
+    }
+    // fail before fetching anything when the store cannot be written in the end
+    if store.is_some() {
+        check_store_support()?;
+    }
+
+    // Wrap client in Arc for sharing across tasks
+    let client = Arc::new(Client::new(client_config.clone())?);
 
     // Generate output filename with current date: pokemon-YYYY-MM-DD.nt
     let now = chrono::Local::now();
-    let filename = format!("pokemon-{}.nt", now.format("%Y-%m-%d"));
+    let filename = output_dir.join(format!("pokemon-{}.nt", now.format("%Y-%m-%d")));
+
+    if !store_only {
+        println!("Writing output to: {}", filename.display());
+    }
+
+    // Write to a temporary file that only replaces pokemon-YYYY-MM-DD.nt once the build succeeded
+    let output_file = AtomicFile::create(&filename)?;
 
-    println!("Writing output to: {}", filename);
+    // Compares every resource with the previous incremental build while it is written
+    let tracker = if incremental {
+        let delta = output_dir.join(format!("pokemon-{}-delta.nt", now.format("%Y-%m-%d")));
+        println!("Writing changed resources to: {}", delta.display());
+        Some(Tracker::open(&output_dir.join(incremental::STATE), &delta)?)
+    } else {
+        None
+    };
 
-    // Create/overwrite the output file in current working directory
-    let output_file = std::fs::File::create(&filename)
-        .map_err(|e| format!("Failed to create output file {}: {}", filename, e))?;
+    // The dump and the tracker see every resource as it is converted, the comments heading each
+    // resource let the next incremental build find them
+    let mut dump = NTriplesSink::new(BufWriter::new(output_file));
+    if incremental {
+        dump = dump.with_comments();
+    }
+    let mut sink = Tee(dump, tracker);
+    let failures = Builder::new()
+        .client(client_config)
+        .failure_mode(mode)
+        .progress(true)
+        .run(client, &mut sink)
+        .await?;
+
+    if mode == FailureMode::Skip {
+        let report = output_dir.join(format!("pokemon-{}-failures.json", now.format("%Y-%m-%d")));
+        println!(
+            "{} resources skipped, see {}",
+            failures.len(),
+            report.display()
+        );
+        write_report(&report, &failures)?;
+    }
+    // checked before anything is published, the temporary dump and delta are dropped
+    if let Some(max) = max_failures {
+        if failures.len() > max {
+            return Err(Error::TooManyFailures {
+                failed: failures.len(),
+                max,
+            });
+        }
+    }
 
-    let m = MultiProgress::new();
-    let _sty = ProgressStyle::with_template(
-        "[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}",
-    )
-    .unwrap()
-    .progress_chars("##-");
+    let Tee(dump, mut tracker) = sink;
+    let mut writer = dump.into_inner();
+    if let Some(tracker) = &mut tracker {
+        tracker.finish(&mut writer, &failures)?;
+    }
 
-    // Create an unbounded channel for sending triples from workers to writer
-    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    let output_file = writer
+        .into_inner()
+        .map_err(|e| Error::io("Error flushing output", e.into_error()))?;
+    if let Some(store) = &store {
+        println!("Loading graph into store: {}", store.display());
+        load_store(store, output_file.temp_path())?;
+    }
 
-    // Spawn a dedicated writer task that consumes from the channel
-    let mut output_file = output_file;
+    // Move the dump into place, or drop it when only the store is wanted
+    if !store_only {
+        output_file.commit()?;
+        update_symlink(&filename, &output_dir.join("pokemon-latest.nt"))?;
+    }
+    if let Some(tracker) = tracker {
+        let changes = tracker.commit(&filename.to_string_lossy())?;
+        println!(
+            "{} resources changed, {} unchanged ({} not converted again), {} kept from the previous dump, {} removed",
+            changes.changed, changes.unchanged, changes.reused, changes.kept, changes.removed
+        );
+    }
+    Ok(failures)
+}
 
-    let writer_handle = tokio::spawn(async move {
-        let mut writer = BufWriter::new(&mut output_file);
-        while let Some(line) = rx.recv().await {
-            if let Err(e) = writeln!(writer, "{}", line) {
-                eprintln!("Error writing to output: {}", e);
-                return Err::<(), Box<dyn Error + Send + Sync>>(e.into());
+/// Reads a dump into memory, the format is picked by the file extension: `.nt`, `.nq`, `.ttl` or
+/// `.trig`. Named graphs of N-Quads and TriG files are merged.
+pub fn read_graph(path: &str) -> Result<Graph, Error> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| Error::io(format!("Failed to open input file {}", path), e))?;
+    let reader = BufReader::new(file);
+    let mut graph = Graph::new();
+    match Path::new(path).extension().and_then(|e| e.to_str()) {
+        Some("nt") => {
+            for triple in NTriplesParser::new().for_reader(reader) {
+                graph.insert(&triple?);
             }
         }
-        writer.flush().map_err(|e| {
-            eprintln!("Error flushing output: {}", e);
-            e
-        })?;
-        Ok(())
-    });
-
-    // Wrap client in Arc for sharing across tasks
-    let client = Arc::new(client);
-
-    // Spawn all conversion tasks concurrently - each sends triples to the channel
-    // let mut handles = vec![];
-
-    // berry endpoints
-    berries::berry_to_nt(&m, client.clone(), tx.clone()).await?;
-    berry_firmness::firmness_to_nt(&m, client.clone(), tx.clone()).await?;
-    berry_flavors::flavors_to_nt(&m, client.clone(), tx.clone()).await?;
-
-    // contests endpoints
-    // TODO contest type
-    // TODO contest effect
-    // TODO super contest effect
-
-    // encounters endpoints
-    // TODO encounter method
-    // TODO encounter condition
-    // TODO encounter condition value
-
-    // evolution endpoints
-    evolutions_chains::evolution_chain_to_nt(&m, client.clone(), tx.clone()).await?;
-    triggers::trigger_to_nt(&m, client.clone(), tx.clone()).await?;
-
-    // games endpoints
-    generations::generation_to_nt(&m, client.clone(), tx.clone()).await?;
-    pokedex::pokedex_to_nt(&m, client.clone(), tx.clone()).await?;
-    // TODO version
-    // TODO version group
-
-    // items endpoints
-    items::item_to_nt(&m, client.clone(), tx.clone()).await?;
-    // TODO item attribute
-    // TODO item category
-    // TODO item fling effect
-    // TODO item pocket
-
-    // locations endpoints
-    locations::location_to_nt(&m, client.clone(), tx.clone()).await?;
-    // TODO location area
-    pal_park::pal_park_area_to_nt(&m, client.clone(), tx.clone()).await?;
-    regions::region_to_nt(&m, client.clone(), tx.clone()).await?;
-
-    // Machines endpoints
-    // TODO machine
-
-    // moves endpoints
-    moves::move_to_nt(&m, client.clone(), tx.clone()).await?;
-    // TODO move ailment
-    // TODO move battle style
-    // TODO move category
-    damage_class::damage_class_to_nt(&m, client.clone(), tx.clone()).await?;
-    // TODO move learn method
-    move_target::move_target_to_nt(&m, client.clone(), tx.clone()).await?;
-
-    // pokemon endpoints
-    abilities::ability_to_nt(&m, client.clone(), tx.clone()).await?;
-    // TODO characteristic
-    egg_groups::egg_group_to_nt(&m, client.clone(), tx.clone()).await?;
-    // TODO gender
-    growth_rates::growth_rate_to_nt(&m, client.clone(), tx.clone()).await?;
-    natures::nature_to_nt(&m, client.clone(), tx.clone()).await?;
-    // TODO pokeathlon stat
-    pokemon::pokemon_to_nt(&m, client.clone(), tx.clone()).await?;
-    // TODO pokemon color
-    forms::form_to_nt(&m, client.clone(), tx.clone()).await?;
-    habitats::habitat_to_nt(&m, client.clone(), tx.clone()).await?;
-    shapes::shape_to_nt(&m, client.clone(), tx.clone()).await?;
-    species::species_to_nt(&m, client.clone(), tx.clone()).await?;
-    stats::stat_to_nt(&m, client.clone(), tx.clone()).await?;
-    poke_types::type_to_nt(&m, client.clone(), tx.clone()).await?;
-
-    // // Wait for all worker tasks to complete
-    // for handle in handles {
-    //     handle.await??;
-    // }
-
-    // Drop the sender to signal the writer that no more data is coming
-    drop(tx);
-
-    // Wait for the writer to finish processing all messages
-    writer_handle.await??;
-
-    // TODO ContestType
-    // TODO EncounterCondition
-    // TODO EncounterConditionValue
-    // TODO MoveLearnMethod
-    // TODO Version
-    // TODO ItemFlingEffect
-    // TODO ItemAttribute
-    // TODO ItemCategory
-    // TODO ItemPocket
-    // TODO ItemCategory
-    // TODO VersionEncounterDetail
-    // TODO EncounterMethod
-    // TODO MoveAilment
-    // TODO MoveCategory
-    // TODO VersionGroup
-    // TODO PokeathonStat
-    // TODO MoveBattleStyle
-    // TODO LocationAreaEncounter
-
-    Ok(())
+        Some("ttl") => {
+            for triple in TurtleParser::new().for_reader(reader) {
+                graph.insert(&triple?);
+            }
+        }
+        Some("nq") => {
+            for quad in NQuadsParser::new().for_reader(reader) {
+                let quad = quad?;
+                graph.insert(TripleRef::new(&quad.subject, &quad.predicate, &quad.object));
+            }
+        }
+        Some("trig") => {
+            for quad in TriGParser::new().for_reader(reader) {
+                let quad = quad?;
+                graph.insert(TripleRef::new(&quad.subject, &quad.predicate, &quad.object));
+            }
+        }
+        _ => {
+            return Err(Error::InvalidData(format!(
+                "Unsupported file format {}, expected .nt, .nq, .ttl or .trig",
+                path
+            )))
+        }
+    }
+    Ok(graph)
 }
 
 // Helper functions to create triples
 fn create_type_triple(
     subject: impl Into<oxrdf::NamedOrBlankNode>,
     class_name: &str,
-) -> Result<Triple, Box<dyn Error + Send + Sync>> {
+) -> Result<Triple, Error> {
     // Use pokemonkg ontology for known classes, POKE namespace only for novel concepts
     let namespace = match class_name {
         "Species" | "Ability" | "Move" | "Type" | "Region" | "Habitat" | "EggGroup"
@@ -188,18 +253,286 @@ fn create_type_triple(
     })
 }
 
+// Localized PokeAPI strings carry their language as the literal's language tag. The tag is the
+// PokeAPI language name, which is also the schema:name of the matching language resource.
+fn create_localized_literal(value: impl Into<String>, language: &str) -> Result<Literal, Error> {
+    Ok(Literal::new_language_tagged_literal(value, language)?)
+}
+
+// A localized string of `subject`: the tagged literal itself, and a poke:localized node that
+// repeats it under the same predicate and links the language resource with schema:inLanguage.
+// The node is labelled by a hash of its content, so the label stays the same between builds.
+fn localized_to_nt(
+    subject: NamedOrBlankNodeRef,
+    predicate: NamedNode,
+    value: impl Into<String>,
+    language: &NamedApiResource<Language>,
+) -> Result<Vec<Triple>, Error> {
+    let literal = create_localized_literal(value, &language.name)?;
+    let hash = Sha256::digest(format!("{subject} {predicate} {literal}"));
+    let node = BlankNode::new(format!("localized{:x}", hash))?;
+    Ok(vec![
+        Triple::new(subject, predicate.clone(), literal.clone()),
+        Triple::new(
+            subject,
+            NamedNode::new(format!("{POKE}localized"))?,
+            node.clone(),
+        ),
+        Triple::new(node.clone(), predicate, literal),
+        Triple::new(
+            node,
+            NamedNode::new(format!("{SCHEMA}inLanguage"))?,
+            NamedNode::new(&language.url)?,
+        ),
+    ])
+}
+
+// Numeric id at the end of a PokeAPI resource URL, e.g. https://pokeapi.co/api/v2/generation/4/
+fn resource_id(url: &str) -> Result<i64, Error> {
+    url.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| Error::InvalidData(format!("no resource id in {}", url)))
+}
+
 fn create_bar_style() -> ProgressStyle {
-    ProgressStyle::with_template("{spinner} {msg}")
-        .unwrap()
+    ProgressStyle::with_template("{spinner} {msg}").unwrap()
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::collections::fixtures;
+    use crate::mock::{Fault, Mock, MockServer};
+    use std::time::Duration;
+
+    // Options for a build against `server` into a fresh directory
+    fn build_options(server: &MockServer, name: &str, mode: FailureMode) -> BuildOptions {
+        let dir = std::env::temp_dir().join(format!("pokemon2rdf-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        BuildOptions {
+            mode,
+            client: ClientConfig {
+                requests_per_second: 0.0,
+                initial_backoff: Duration::from_millis(1),
+                base_url: server.base_url(),
+                ..ClientConfig::default()
+            },
+            output_dir: dir,
+            ..BuildOptions::default()
+        }
+    }
+
+    // `pokemon-YYYY-MM-DD{suffix}` in `dir`
+    fn output_path(dir: &Path, suffix: &str) -> PathBuf {
+        dir.join(format!(
+            "pokemon-{}{suffix}",
+            chrono::Local::now().format("%Y-%m-%d")
+        ))
+    }
 
     #[tokio::test]
     async fn test_rdf() {
-        assert!((build_graph().await).is_ok())
+        let server = Mock::new(fixtures::corpus())
+            .page_size(2)
+            .latency(Duration::from_millis(1))
+            .fail("pokemon/1/", Fault::ServerError(2))
+            .start()
+            .await;
+        let options = BuildOptions {
+            incremental: true,
+            ..build_options(&server, "build", FailureMode::Abort)
+        };
+        let dir = options.output_dir.clone();
+        assert_eq!(build_graph_with(options.clone()).await.unwrap(), vec![]);
+
+        let dump = std::fs::read_to_string(output_path(&dir, ".nt")).unwrap();
+        fixtures::assert_golden_nt("build", &dump);
+        // everything is new to the first incremental build
+        let delta = std::fs::read_to_string(output_path(&dir, "-delta.nt")).unwrap();
+        assert_eq!(delta, dump);
+        assert!(dump.starts_with("# https://pokeapi.co/api/v2/berry/1/\n"));
+        assert!(dump
+            .lines()
+            .any(|line| line == "# https://pokeapi.co/api/v2/pokemon/1/"));
+
+        // lists are read in two pages, failed requests are retried
+        let requests = server.requests();
+        assert!(requests.contains(&"pokemon/?offset=2&limit=2".to_string()));
+        assert_eq!(requests.iter().filter(|r| *r == "pokemon/1/").count(), 3);
+
+        // the next build takes resources with unchanged JSON from the dump instead of converting
+        // them, so a triple planted there shows up again
+        let planted =
+            "<https://pokeapi.co/api/v2/berry/1/> <https://schema.org/name> \"planted\" .";
+        let tampered = dump.replacen(
+            "# https://pokeapi.co/api/v2/berry/1/\n",
+            &format!("# https://pokeapi.co/api/v2/berry/1/\n{planted}\n"),
+            1,
+        );
+        std::fs::write(output_path(&dir, ".nt"), &tampered).unwrap();
+        assert_eq!(build_graph_with(options).await.unwrap(), vec![]);
+        let dump = std::fs::read_to_string(output_path(&dir, ".nt")).unwrap();
+        assert_eq!(dump, tampered);
+        // only berry 1 changed, its triples differ from the hash recorded by the first build
+        let delta = std::fs::read_to_string(output_path(&dir, "-delta.nt")).unwrap();
+        let berry_end = dump[1..].find("\n# ").unwrap() + 2;
+        assert_eq!(delta, dump[..berry_end]);
+        // every resource is still fetched once to compare its JSON
+        let requests = server.requests();
+        assert_eq!(requests.iter().filter(|r| *r == "pokemon/1/").count(), 4);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // A copy of the corpus in `dir` whose responses a test can change between builds
+    fn copy_corpus(from: &Path, to: &Path) {
+        std::fs::create_dir_all(to).unwrap();
+        for entry in std::fs::read_dir(from).unwrap() {
+            let entry = entry.unwrap();
+            let target = to.join(entry.file_name());
+            if entry.file_type().unwrap().is_dir() {
+                copy_corpus(&entry.path(), &target);
+            } else {
+                std::fs::copy(entry.path(), target).unwrap();
+            }
+        }
+    }
+
+    // The encounters of a Pokémon are fetched separately, a change there alone is not hidden by
+    // reusing the Pokémon's triples
+    #[tokio::test]
+    async fn test_rdf_encounters() {
+        let corpus = std::env::temp_dir().join(format!(
+            "pokemon2rdf-encounters-corpus-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&corpus);
+        copy_corpus(&fixtures::corpus(), &corpus);
+        let server = Mock::new(&corpus).start().await;
+        let options = BuildOptions {
+            incremental: true,
+            ..build_options(&server, "encounters", FailureMode::Abort)
+        };
+        let dir = options.output_dir.clone();
+        assert_eq!(build_graph_with(options.clone()).await.unwrap(), vec![]);
+
+        let encounters = corpus.join("pokemon/1/encounters.json");
+        let json = std::fs::read_to_string(&encounters).unwrap();
+        let moved = json.replace("location-area/285/", "location-area/286/");
+        assert_ne!(moved, json);
+        std::fs::write(&encounters, moved).unwrap();
+        assert_eq!(build_graph_with(options).await.unwrap(), vec![]);
+
+        let area = "<https://pokeapi.co/api/v2/location-area/286/>";
+        let dump = std::fs::read_to_string(output_path(&dir, ".nt")).unwrap();
+        assert!(dump.contains(area));
+        let delta = std::fs::read_to_string(output_path(&dir, "-delta.nt")).unwrap();
+        assert!(delta.starts_with("# https://pokeapi.co/api/v2/pokemon/1/\n"));
+        assert!(delta.contains(area));
+        assert!(!delta.contains("<https://pokeapi.co/api/v2/location-area/285/>"));
+        std::fs::remove_dir_all(&dir).unwrap();
+        std::fs::remove_dir_all(&corpus).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rdf_not_found() {
+        let server = Mock::new(fixtures::corpus())
+            .fail("pokemon/133/", Fault::NotFound)
+            .start()
+            .await;
+
+        let options = build_options(&server, "abort", FailureMode::Abort);
+        let dir = options.output_dir.clone();
+        assert!(build_graph_with(options).await.is_err());
+        assert!(!output_path(&dir, ".nt").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let options = build_options(&server, "skip", FailureMode::Skip);
+        let dir = options.output_dir.clone();
+        let failures = build_graph_with(options).await.unwrap();
+        assert!(output_path(&dir, "-failures.json").exists());
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].url, "https://pokeapi.co/api/v2/pokemon/133/");
+        assert!(!failures[0].retryable);
+        let dump = std::fs::read_to_string(output_path(&dir, ".nt")).unwrap();
+        // without --incremental resources are not headed by comments
+        assert!(!dump.contains("\n# "));
+        assert!(dump.contains("\n<https://pokeapi.co/api/v2/pokemon/1/> "));
+        assert!(!dump.contains("\n<https://pokeapi.co/api/v2/pokemon/133/> "));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // Over the threshold only the failure report is written
+    #[tokio::test]
+    async fn test_rdf_max_failures() {
+        let server = Mock::new(fixtures::corpus())
+            .fail("pokemon/133/", Fault::NotFound)
+            .start()
+            .await;
+        let options = BuildOptions {
+            max_failures: Some(0),
+            incremental: true,
+            ..build_options(&server, "max-failures", FailureMode::Skip)
+        };
+        let dir = options.output_dir.clone();
+        assert!(matches!(
+            build_graph_with(options).await,
+            Err(Error::TooManyFailures { failed: 1, max: 0 })
+        ));
+        assert!(output_path(&dir, "-failures.json").exists());
+        assert!(!output_path(&dir, ".nt").exists());
+        assert!(!output_path(&dir, "-delta.nt").exists());
+        assert!(!dir.join("pokemon-latest.nt").exists());
+        assert!(!dir.join(incremental::STATE).exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // With the rocksdb feature the graph only goes into the store, without it nothing is fetched
+    #[tokio::test]
+    async fn test_rdf_store() {
+        let server = Mock::new(fixtures::corpus()).start().await;
+        let options = build_options(&server, "store", FailureMode::Abort);
+        let dir = options.output_dir.clone();
+        let store = dir.join("store");
+        let result = build_graph_with(BuildOptions {
+            store: Some(store.clone()),
+            store_only: true,
+            ..options
+        })
+        .await;
+        assert!(!output_path(&dir, ".nt").exists());
+        if cfg!(feature = "rocksdb") {
+            result.unwrap();
+            let mut output = vec![];
+            crate::store::query(
+                &crate::store::open_store(&store).unwrap(),
+                "ASK { <https://pokeapi.co/api/v2/pokemon/1/> ?p ?o }",
+                crate::store::ResultFormat::Table,
+                &mut output,
+            )
+            .unwrap();
+            assert_eq!(String::from_utf8(output).unwrap(), "true\n");
+        } else {
+            assert!(matches!(result, Err(Error::InvalidData(_))));
+            assert_eq!(server.requests(), Vec::<String>::new());
+            assert!(!store.exists());
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_resource_id() {
+        assert_eq!(
+            resource_id("https://pokeapi.co/api/v2/generation/4/").unwrap(),
+            4
+        );
+        assert_eq!(
+            resource_id("https://pokeapi.co/api/v2/type/10002").unwrap(),
+            10002
+        );
+        assert!(resource_id("https://pokeapi.co/api/v2/type/").is_err());
     }
 }
@@ -1,9 +1,412 @@
-use pokemon2rdf::build_graph;
+use clap::{Parser, Subcommand, ValueEnum};
+use pokemon2rdf::assets::{mirror_assets_file, AssetSource};
+use pokemon2rdf::client::USER_AGENT;
+use pokemon2rdf::diff::{diff_files, PatchFormat};
+use pokemon2rdf::serve::serve;
+use pokemon2rdf::sink::{NTriplesSink, TurtleSink};
+use pokemon2rdf::snapshot::{snapshot_file, Scope};
+use pokemon2rdf::store::{memory_store, open_store, query, ResultFormat};
+use pokemon2rdf::{build_graph_with, BuildOptions, Builder, ClientConfig, Collection, FailureMode};
+use std::io::BufWriter;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(version, about = "Convert PokéAPI data into RDF")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Build the full graph from PokéAPI (the default)
+    Build {
+        /// Skip resources that fail to fetch and list them in a JSON report instead of aborting
+        #[arg(long)]
+        keep_going: bool,
+        /// Fail without publishing the dump when more resources than this were skipped
+        #[arg(long, default_value_t = 0, requires = "keep_going")]
+        max_failures: usize,
+        /// Requests per second sent to PokéAPI across all collections
+        #[arg(long, default_value_t = ClientConfig::default().requests_per_second)]
+        requests_per_second: f64,
+        /// Retries for requests that failed with a timeout, connection error, 429 or 5xx
+        #[arg(long, default_value_t = ClientConfig::default().max_retries)]
+        max_retries: u32,
+        /// User-Agent sent to PokéAPI
+        #[arg(long, default_value = USER_AGENT)]
+        user_agent: String,
+        /// PokéAPI instance to fetch from, e.g. a local mirror
+        #[arg(long, default_value_t = ClientConfig::default().base_url)]
+        base_url: String,
+        /// Compare with the previous incremental build and write changed resources to a delta file
+        #[arg(long)]
+        incremental: bool,
+        /// Also load the graph into an embedded on-disk store in this directory (rocksdb feature)
+        #[arg(long)]
+        store: Option<PathBuf>,
+        /// Only fill the store, without writing the N-Triples dump
+        #[arg(long, requires = "store", conflicts_with = "incremental")]
+        store_only: bool,
+    },
+    /// Convert a single resource and print it, e.g. `get pokemon pikachu`
+    Get {
+        /// PokéAPI endpoint, e.g. pokemon, pokemon-species or type
+        collection: Collection,
+        /// Id or name of the resource
+        id: String,
+        /// Also convert the resources it links to, up to this many links away
+        #[arg(long, default_value_t = 0)]
+        depth: usize,
+        /// Skip linked resources that fail to fetch instead of aborting
+        #[arg(long)]
+        keep_going: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = GetFormat::Ntriples)]
+        format: GetFormat,
+        /// PokéAPI instance to fetch from, e.g. a local mirror
+        #[arg(long, default_value_t = ClientConfig::default().base_url)]
+        base_url: String,
+    },
+    /// Project an existing dump to what existed in a generation or version group
+    Snapshot {
+        /// N-Triples dump produced by `build`
+        #[arg(long)]
+        input: String,
+        /// Generation number, e.g. 4
+        #[arg(
+            long,
+            conflicts_with = "version_group",
+            required_unless_present = "version_group"
+        )]
+        generation: Option<i64>,
+        /// Version group id, e.g. 8 for diamond-pearl
+        #[arg(long)]
+        version_group: Option<i64>,
+        /// Output file, defaults to the input name with the scope appended
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Mirror every sprite of an existing dump into a local, content-addressed directory
+    Assets {
+        /// N-Triples dump produced by `build`
+        #[arg(long)]
+        input: String,
+        /// Directory the sprites are stored in, reruns skip what is already there
+        #[arg(long, default_value = "assets")]
+        store: PathBuf,
+        /// Read sprites from a local copy of the PokeAPI/sprites repository instead of downloading
+        #[arg(long)]
+        offline: Option<PathBuf>,
+        /// URL the store is served from, defaults to a file:// URL of the store
+        #[arg(long)]
+        base_url: Option<String>,
+        /// Output file, defaults to the input name with "-assets" appended
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Compare two dumps and summarize what changed, optionally writing a patch
+    Diff {
+        /// Older dump (.nt, .nq, .ttl or .trig)
+        #[arg(long)]
+        old: String,
+        /// Newer dump (.nt, .nq, .ttl or .trig)
+        #[arg(long)]
+        new: String,
+        /// Write the added and removed triples to this file
+        #[arg(long)]
+        output: Option<String>,
+        /// Patch format of the output
+        #[arg(long, value_enum, default_value_t = DiffFormat::Patch, requires = "output")]
+        format: DiffFormat,
+    },
+    /// Run a SPARQL query against a store written by `build --store`, or against a dump
+    Query {
+        /// SPARQL query file, e.g. examples/pokemon-move-by-stat.rq
+        #[arg(long)]
+        query: PathBuf,
+        /// Store directory written by `build --store`
+        #[arg(long, conflicts_with = "input", required_unless_present = "input")]
+        store: Option<PathBuf>,
+        /// N-Triples dump to load into memory instead of opening a store
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// How results are printed, CONSTRUCT and DESCRIBE results are always N-Triples
+        #[arg(long, value_enum, default_value_t = QueryFormat::Table)]
+        format: QueryFormat,
+    },
+    /// Serve a dump or store over HTTP as a SPARQL endpoint on localhost
+    Serve {
+        /// N-Triples dump, or store directory written by `build --store`
+        #[arg(long)]
+        data: PathBuf,
+        /// Port to listen on
+        #[arg(long, default_value_t = 7878)]
+        port: u16,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum QueryFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum GetFormat {
+    Ntriples,
+    Turtle,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum DiffFormat {
+    /// RDF Patch
+    Patch,
+    /// SPARQL 1.1 Update
+    Sparql,
+}
 
 #[tokio::main]
 async fn main() {
-    match build_graph().await {
-        Ok(_) => println!("Graph built successfully."),
-        Err(e) => eprintln!("Error building graph: {}", e),
-    };
+    let cli = Cli::parse();
+    let command = cli.command.unwrap_or(Command::Build {
+        keep_going: false,
+        max_failures: 0,
+        requests_per_second: ClientConfig::default().requests_per_second,
+        max_retries: ClientConfig::default().max_retries,
+        user_agent: USER_AGENT.to_string(),
+        base_url: ClientConfig::default().base_url,
+        incremental: false,
+        store: None,
+        store_only: false,
+    });
+    match command {
+        Command::Build {
+            keep_going,
+            max_failures,
+            requests_per_second,
+            max_retries,
+            user_agent,
+            base_url,
+            incremental,
+            store,
+            store_only,
+        } => {
+            let client = ClientConfig {
+                requests_per_second,
+                max_retries,
+                user_agent,
+                base_url,
+                ..ClientConfig::default()
+            };
+            let mode = if keep_going {
+                FailureMode::Skip
+            } else {
+                FailureMode::Abort
+            };
+            let options = BuildOptions {
+                mode,
+                max_failures: keep_going.then_some(max_failures),
+                client,
+                incremental,
+                store,
+                store_only,
+                ..BuildOptions::default()
+            };
+            match build_graph_with(options).await {
+                Ok(_) => println!("Graph built successfully."),
+                Err(e) => {
+                    eprintln!("Error building graph: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Get {
+            collection,
+            id,
+            depth,
+            keep_going,
+            format,
+            base_url,
+        } => {
+            let mode = if keep_going {
+                FailureMode::Skip
+            } else {
+                FailureMode::Abort
+            };
+            let builder = Builder::new().failure_mode(mode).client(ClientConfig {
+                base_url,
+                ..ClientConfig::default()
+            });
+            let output = BufWriter::new(std::io::stdout());
+            let result = match format {
+                GetFormat::Ntriples => {
+                    let sink = NTriplesSink::new(output);
+                    builder.resource(collection, &id, depth, sink).await
+                }
+                GetFormat::Turtle => match TurtleSink::new(output) {
+                    Ok(sink) => builder.resource(collection, &id, depth, sink).await,
+                    Err(e) => Err(e),
+                },
+            };
+            match result {
+                Ok(failures) => {
+                    for f in failures {
+                        eprintln!("Skipped {}: {}", f.url, f.error);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error converting {} {}: {}", collection.endpoint(), id, e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Snapshot {
+            input,
+            generation,
+            version_group,
+            output,
+        } => {
+            let (scope, suffix) = match (generation, version_group) {
+                (Some(g), _) => (Scope::Generation(g), format!("generation-{g}")),
+                (None, Some(vg)) => (Scope::VersionGroup(vg), format!("version-group-{vg}")),
+                (None, None) => unreachable!("clap requires a generation or version group"),
+            };
+            let output = output
+                .unwrap_or_else(|| format!("{}-{}.nt", input.trim_end_matches(".nt"), suffix));
+            println!("Writing snapshot to: {}", output);
+            match snapshot_file(&input, &output, scope) {
+                Ok(_) => println!("Snapshot built successfully."),
+                Err(e) => {
+                    eprintln!("Error building snapshot: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Assets {
+            input,
+            store,
+            offline,
+            base_url,
+            output,
+        } => {
+            let source = match offline {
+                Some(dir) => AssetSource::Local(dir),
+                None => AssetSource::Remote,
+            };
+            let base_url = match base_url {
+                Some(url) => url,
+                None => match std::fs::create_dir_all(&store).and_then(|_| store.canonicalize()) {
+                    Ok(path) => format!("file://{}/", path.display()),
+                    Err(e) => {
+                        eprintln!("Error creating asset directory {}: {}", store.display(), e);
+                        return;
+                    }
+                },
+            };
+            let output =
+                output.unwrap_or_else(|| format!("{}-assets.nt", input.trim_end_matches(".nt")));
+            println!("Writing output to: {}", output);
+            match mirror_assets_file(&input, &output, &store, &source, &base_url).await {
+                Ok(failures) => {
+                    for f in &failures {
+                        eprintln!("Skipped {}: {}", f.url, f.error);
+                    }
+                    if failures.is_empty() {
+                        println!("Assets mirrored successfully.");
+                    } else {
+                        println!(
+                            "{} sprites could not be mirrored and keep their URL",
+                            failures.len()
+                        );
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error mirroring assets: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Diff {
+            old,
+            new,
+            output,
+            format,
+        } => {
+            let format = match format {
+                DiffFormat::Patch => PatchFormat::RdfPatch,
+                DiffFormat::Sparql => PatchFormat::SparqlUpdate,
+            };
+            match diff_files(&old, &new, output.as_deref(), format) {
+                Ok(diff) => {
+                    println!(
+                        "{} triples added, {} removed",
+                        diff.added.len(),
+                        diff.removed.len()
+                    );
+                    for (class, changes) in diff.summary() {
+                        println!(
+                            "{}: {} changed, {} added, {} removed",
+                            class, changes.changed, changes.added, changes.removed
+                        );
+                    }
+                    if let Some(output) = output {
+                        println!("Patch written to: {}", output);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error comparing dumps: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Query {
+            query: query_file,
+            store,
+            input,
+            format,
+        } => {
+            let format = match format {
+                QueryFormat::Table => ResultFormat::Table,
+                QueryFormat::Csv => ResultFormat::Csv,
+                QueryFormat::Json => ResultFormat::Json,
+            };
+            let sparql = match std::fs::read_to_string(&query_file) {
+                Ok(sparql) => sparql,
+                Err(e) => {
+                    eprintln!("Error reading {}: {}", query_file.display(), e);
+                    std::process::exit(1);
+                }
+            };
+            let store = match (store, input) {
+                (Some(store), _) => open_store(&store),
+                (None, Some(input)) => memory_store(&input),
+                (None, None) => unreachable!("clap requires --store or --input"),
+            };
+            if let Err(e) =
+                store.and_then(|store| query(&store, &sparql, format, std::io::stdout().lock()))
+            {
+                eprintln!("Error running query: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Command::Serve { data, port } => {
+            println!("Loading {}", data.display());
+            let store = if data.is_dir() {
+                open_store(&data)
+            } else {
+                memory_store(&data)
+            };
+            let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+            if let Err(e) = match store {
+                Ok(store) => serve(store, addr).await,
+                Err(e) => Err(e),
+            } {
+                eprintln!("Error serving {}: {}", data.display(), e);
+                std::process::exit(1);
+            }
+        }
+    }
 }
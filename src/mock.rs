@@ -0,0 +1,219 @@
+// A stand-in for PokeAPI for end-to-end tests: serves a directory of recorded responses (see
+// tests/fixtures/README.md) over HTTP, pages lists like PokeAPI and can be told to be slow or
+// to fail for some paths.
+
+use http_body_util::Full;
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{CACHE_CONTROL, CONTENT_TYPE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+// PokeAPI's page size when a list is requested without `limit`
+const PAGE_SIZE: usize = 20;
+
+// How a path misbehaves
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Fault {
+    // 404 for every request
+    NotFound,
+    // 500 for the given number of requests, answered normally afterwards
+    ServerError(usize),
+}
+
+#[derive(Debug)]
+pub(crate) struct Mock {
+    dir: PathBuf,
+    page_size: usize,
+    latency: Duration,
+    faults: HashMap<String, Fault>,
+}
+
+impl Mock {
+    pub(crate) fn new(dir: impl Into<PathBuf>) -> Self {
+        Mock {
+            dir: dir.into(),
+            page_size: PAGE_SIZE,
+            latency: Duration::ZERO,
+            faults: HashMap::new(),
+        }
+    }
+
+    // Entries of a list page when the request has no `limit`
+    pub(crate) fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    // Delay before every response
+    pub(crate) fn latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    // `path` is below the API root without query, e.g. "pokemon/1/"
+    pub(crate) fn fail(mut self, path: &str, fault: Fault) -> Self {
+        self.faults.insert(path.to_string(), fault);
+        self
+    }
+
+    pub(crate) async fn start(self) -> MockServer {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let state = Arc::new(State {
+            mock: self,
+            base_url: format!("http://{addr}/api/v2/"),
+            faults: Mutex::default(),
+            requests: Mutex::default(),
+        });
+        let server = state.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let state = server.clone();
+                tokio::spawn(async move {
+                    let service = service_fn(move |req| handle(state.clone(), req));
+                    let _ = http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+        MockServer { state, handle }
+    }
+}
+
+// Stops serving when dropped
+#[derive(Debug)]
+pub(crate) struct MockServer {
+    state: Arc<State>,
+    handle: JoinHandle<()>,
+}
+
+impl MockServer {
+    // Stands in for `https://pokeapi.co/api/v2/` in `ClientConfig::base_url`
+    pub(crate) fn base_url(&self) -> String {
+        self.state.base_url.clone()
+    }
+
+    // Every request so far, as path and query below the API root, e.g. "pokemon/?offset=2&limit=3"
+    pub(crate) fn requests(&self) -> Vec<String> {
+        self.state.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    mock: Mock,
+    base_url: String,
+    // requests answered with a fault so far, per path
+    faults: Mutex<HashMap<String, usize>>,
+    requests: Mutex<Vec<String>>,
+}
+
+async fn handle(
+    state: Arc<State>,
+    req: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, hyper::Error> {
+    tokio::time::sleep(state.mock.latency).await;
+    let path = req
+        .uri()
+        .path()
+        .strip_prefix("/api/v2/")
+        .unwrap_or_default();
+    let query = req.uri().query().unwrap_or_default();
+    state.requests.lock().unwrap().push(match query {
+        "" => path.to_string(),
+        query => format!("{path}?{query}"),
+    });
+    Ok(respond(&state, path, query))
+}
+
+fn respond(state: &State, path: &str, query: &str) -> Response<Full<Bytes>> {
+    match state.mock.faults.get(path) {
+        Some(Fault::NotFound) => return status(StatusCode::NOT_FOUND),
+        Some(Fault::ServerError(times)) => {
+            let mut faults = state.faults.lock().unwrap();
+            let count = faults.entry(path.to_string()).or_default();
+            if *count < *times {
+                *count += 1;
+                return status(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+        None => {}
+    }
+    let file = state
+        .mock
+        .dir
+        .join(format!("{}.json", path.trim_end_matches('/')));
+    let Ok(body) = std::fs::read(&file) else {
+        return status(StatusCode::NOT_FOUND);
+    };
+    let body = match serde_json::from_slice::<Value>(&body) {
+        Ok(list) if list.get("results").is_some_and(Value::is_array) => {
+            page(state, path, query, list).to_string().into_bytes()
+        }
+        _ => body,
+    };
+    Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        // keep the responses out of the client's on-disk cache
+        .header(CACHE_CONTROL, "no-store")
+        .body(Full::new(Bytes::from(body)))
+        .unwrap()
+}
+
+// The page of a list fixture selected by `offset` and `limit`, with PokeAPI's links to the
+// neighbouring pages
+fn page(state: &State, path: &str, query: &str, mut list: Value) -> Value {
+    let param = |name: &str| {
+        form_urlencoded::parse(query.as_bytes())
+            .find(|(n, _)| n == name)
+            .and_then(|(_, v)| v.parse::<usize>().ok())
+    };
+    let offset = param("offset").unwrap_or(0);
+    let limit = param("limit").unwrap_or(state.mock.page_size);
+    let results = list["results"].as_array().cloned().unwrap_or_default();
+    let count = results.len();
+    let link = |offset: usize| {
+        Value::String(format!(
+            "{}{path}?offset={offset}&limit={limit}",
+            state.base_url
+        ))
+    };
+    list["count"] = count.into();
+    list["next"] = if offset + limit < count {
+        link(offset + limit)
+    } else {
+        Value::Null
+    };
+    list["previous"] = if offset > 0 {
+        link(offset.saturating_sub(limit))
+    } else {
+        Value::Null
+    };
+    list["results"] = results.into_iter().skip(offset).take(limit).collect();
+    list
+}
+
+fn status(status: StatusCode) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .body(Full::new(Bytes::from(status.to_string())))
+        .unwrap()
+}
@@ -0,0 +1,156 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::Error;
+
+// A file that only appears under its final name once it is complete. Data goes to a hidden
+// temporary file in the same directory, `commit` syncs it to disk and renames it into place.
+// Dropping it without committing removes the temporary file.
+#[derive(Debug)]
+pub(crate) struct AtomicFile {
+    file: Option<File>,
+    temp: PathBuf,
+    path: PathBuf,
+}
+
+impl AtomicFile {
+    pub(crate) fn create(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+        let name = path
+            .file_name()
+            .ok_or_else(|| Error::InvalidData(format!("{} is not a file path", path.display())))?;
+        // same directory, so the rename does not cross file systems
+        let temp = path.with_file_name(format!(
+            ".{}.{}.partial",
+            name.to_string_lossy(),
+            std::process::id()
+        ));
+        let file = File::create(&temp)
+            .map_err(|e| Error::io(format!("Failed to create {}", temp.display()), e))?;
+        Ok(AtomicFile {
+            file: Some(file),
+            temp,
+            path,
+        })
+    }
+
+    pub(crate) fn file(&mut self) -> &mut File {
+        self.file.as_mut().expect("file is only taken by commit")
+    }
+
+    // Where the data is while it is written, for readers that need it before the commit
+    pub(crate) fn temp_path(&self) -> &Path {
+        &self.temp
+    }
+
+    // Flushes the data to disk and moves the file to its final name
+    pub(crate) fn commit(mut self) -> Result<(), Error> {
+        let file = self.file.take().expect("file is only taken by commit");
+        file.sync_all()
+            .map_err(|e| Error::io(format!("Failed to sync {}", self.temp.display()), e))?;
+        drop(file);
+        std::fs::rename(&self.temp, &self.path).map_err(|e| {
+            Error::io(
+                format!(
+                    "Failed to move output into place at {}",
+                    self.path.display()
+                ),
+                e,
+            )
+        })?;
+        // make the rename itself durable
+        #[cfg(unix)]
+        if let Ok(dir) = File::open(parent(&self.path)) {
+            let _ = dir.sync_all();
+        }
+        Ok(())
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.file().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file().flush()
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        if self.file.take().is_some() {
+            let _ = std::fs::remove_file(&self.temp);
+        }
+    }
+}
+
+/// Points the symlink `link` at `target`, replacing an existing link without a moment where
+/// `link` is missing. Does nothing on platforms without symlinks.
+pub(crate) fn update_symlink(target: &Path, link: &Path) -> Result<(), Error> {
+    #[cfg(unix)]
+    {
+        // relative to the link, so the directory can be moved or mounted elsewhere
+        let target = target.file_name().map(Path::new).unwrap_or(target);
+        let temp = link.with_file_name(format!(
+            ".{}.{}.partial",
+            link.file_name().unwrap_or_default().to_string_lossy(),
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&temp);
+        std::os::unix::fs::symlink(target, &temp)
+            .map_err(|e| Error::io(format!("Failed to create link {}", temp.display()), e))?;
+        std::fs::rename(&temp, link)
+            .map_err(|e| Error::io(format!("Failed to update link {}", link.display()), e))?;
+    }
+    #[cfg(not(unix))]
+    let _ = (target, link);
+    Ok(())
+}
+
+fn parent(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_atomic_file() {
+        let dir = std::env::temp_dir().join(format!("pokemon2rdf-output-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("pokemon-test.nt");
+
+        let mut output = AtomicFile::create(&path).unwrap();
+        writeln!(output.file(), "<a> <b> <c> .").unwrap();
+        assert!(!path.exists());
+        drop(output);
+        assert!(!path.exists());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+        let mut output = AtomicFile::create(&path).unwrap();
+        writeln!(output.file(), "<a> <b> <c> .").unwrap();
+        output.commit().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "<a> <b> <c> .\n");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        #[cfg(unix)]
+        {
+            let link = dir.join("pokemon-latest.nt");
+            update_symlink(&path, &link).unwrap();
+            update_symlink(&path, &link).unwrap();
+            assert_eq!(
+                std::fs::read_link(&link).unwrap(),
+                Path::new("pokemon-test.nt")
+            );
+            assert_eq!(std::fs::read_to_string(&link).unwrap(), "<a> <b> <c> .\n");
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
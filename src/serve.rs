@@ -0,0 +1,368 @@
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{ACCEPT, CONTENT_TYPE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use oxigraph::io::{RdfFormat, RdfSerializer};
+use oxigraph::sparql::results::{QueryResultsFormat, QueryResultsSerializer};
+use oxigraph::sparql::{QueryResults, SparqlEvaluator};
+use oxigraph::store::Store;
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+
+use crate::Error;
+use crate::{POKEAPI, PREFIXES};
+
+// Media types for SELECT and ASK results, the first one is the default
+static RESULT_TYPES: [(&str, QueryResultsFormat); 5] = [
+    ("application/sparql-results+json", QueryResultsFormat::Json),
+    ("application/json", QueryResultsFormat::Json),
+    ("application/sparql-results+xml", QueryResultsFormat::Xml),
+    ("text/csv", QueryResultsFormat::Csv),
+    ("text/tab-separated-values", QueryResultsFormat::Tsv),
+];
+
+// Media types for CONSTRUCT and DESCRIBE results and dereferenced resources
+static GRAPH_TYPES: [(&str, RdfFormat); 2] = [
+    ("text/turtle", RdfFormat::Turtle),
+    ("application/n-triples", RdfFormat::NTriples),
+];
+
+/// Serves `store` on `addr` until the process is stopped:
+///
+/// - `/sparql`: SPARQL 1.1 Protocol query endpoint, `GET ?query=...` or `POST` as form or
+///   `application/sparql-query`
+/// - `/{endpoint}/{id}`: the description of `https://pokeapi.co/api/v2/{endpoint}/{id}/`,
+///   e.g. `/pokemon/25`
+pub async fn serve(store: Store, addr: SocketAddr) -> Result<(), Error> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| Error::io(format!("Failed to listen on {}", addr), e))?;
+    println!("SPARQL endpoint at http://{}/sparql", addr);
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let store = store.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |req| handle(store.clone(), req));
+            if let Err(e) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                eprintln!("Error serving connection: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle(
+    store: Store,
+    req: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, hyper::Error> {
+    let (parts, body) = req.into_parts();
+    let body = body.collect().await?.to_bytes();
+    let req = Request::from_parts(parts, body);
+    // queries run on the blocking pool so a slow one does not hold up the other connections
+    Ok(tokio::task::spawn_blocking(move || respond(&store, &req))
+        .await
+        .unwrap_or_else(|e| text(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())))
+}
+
+fn respond(store: &Store, req: &Request<Bytes>) -> Response<Full<Bytes>> {
+    let path = req.uri().path();
+    let accept = req
+        .headers()
+        .get(ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("*/*");
+    if path == "/sparql" {
+        return match sparql_query(req) {
+            Ok(Some(query)) => run(store, &query, accept),
+            Ok(None) => text(StatusCode::BAD_REQUEST, "Missing query parameter"),
+            Err((status, message)) => text(status, message),
+        };
+    }
+    if req.method() != Method::GET {
+        return text(StatusCode::METHOD_NOT_ALLOWED, "Only GET is supported");
+    }
+    let resource = path.trim_matches('/');
+    if resource.is_empty() || !resource.contains('/') {
+        return text(
+            StatusCode::NOT_FOUND,
+            "Query /sparql or get a resource like /pokemon/25",
+        );
+    }
+    let Some((content_type, format)) = negotiate(accept, &GRAPH_TYPES) else {
+        return not_acceptable(&GRAPH_TYPES);
+    };
+    let iri = format!("{POKEAPI}{resource}/");
+    match describe(store, &iri, format) {
+        Ok(Some(body)) => content(content_type, body),
+        Ok(None) => text(StatusCode::NOT_FOUND, format!("No triples about {iri}")),
+        Err(e) => text(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+// The query of a SPARQL 1.1 Protocol request
+fn sparql_query(req: &Request<Bytes>) -> Result<Option<String>, (StatusCode, &'static str)> {
+    let parameter = |params: &[u8]| {
+        form_urlencoded::parse(params)
+            .find(|(name, _)| name == "query")
+            .map(|(_, value)| value.into_owned())
+    };
+    match *req.method() {
+        Method::GET => Ok(parameter(req.uri().query().unwrap_or_default().as_bytes())),
+        Method::POST => {
+            let content_type = req
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default();
+            match content_type.split(';').next().unwrap_or_default().trim() {
+                "application/sparql-query" => {
+                    Ok(Some(String::from_utf8_lossy(req.body()).into_owned()))
+                }
+                "application/x-www-form-urlencoded" => Ok(parameter(req.body())),
+                _ => Err((
+                    StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                    "Send application/sparql-query or application/x-www-form-urlencoded",
+                )),
+            }
+        }
+        _ => Err((
+            StatusCode::METHOD_NOT_ALLOWED,
+            "Use GET or POST for queries",
+        )),
+    }
+}
+
+fn run(store: &Store, query: &str, accept: &str) -> Response<Full<Bytes>> {
+    let query = match SparqlEvaluator::new().parse_query(query) {
+        Ok(query) => query,
+        Err(e) => return text(StatusCode::BAD_REQUEST, e.to_string()),
+    };
+    let results = match query.on_store(store).execute() {
+        Ok(results) => results,
+        Err(e) => return text(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    };
+    let serialized = match results {
+        QueryResults::Graph(triples) => {
+            let Some((content_type, format)) = negotiate(accept, &GRAPH_TYPES) else {
+                return not_acceptable(&GRAPH_TYPES);
+            };
+            serialize_graph(triples, format).map(|body| (content_type, body))
+        }
+        results => {
+            let Some((content_type, format)) = negotiate(accept, &RESULT_TYPES) else {
+                return not_acceptable(&RESULT_TYPES);
+            };
+            serialize_results(results, format).map(|body| (content_type, body))
+        }
+    };
+    match serialized {
+        Ok((content_type, body)) => content(content_type, body),
+        Err(e) => text(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+// Concise bounded description of a resource, None if there is nothing about it
+fn describe(store: &Store, iri: &str, format: RdfFormat) -> Result<Option<Vec<u8>>, Error> {
+    let results = SparqlEvaluator::new()
+        .parse_query(&format!("DESCRIBE <{iri}>"))?
+        .on_store(store)
+        .execute()?;
+    let QueryResults::Graph(triples) = results else {
+        return Ok(None);
+    };
+    let mut triples = triples.peekable();
+    if triples.peek().is_none() {
+        return Ok(None);
+    }
+    serialize_graph(triples, format).map(Some)
+}
+
+fn serialize_graph(
+    triples: impl Iterator<
+        Item = Result<oxigraph::model::Triple, oxigraph::sparql::QueryEvaluationError>,
+    >,
+    format: RdfFormat,
+) -> Result<Vec<u8>, Error> {
+    let mut serializer = RdfSerializer::from_format(format);
+    if format == RdfFormat::Turtle {
+        for (prefix, iri) in PREFIXES {
+            serializer = serializer.with_prefix(prefix, iri)?;
+        }
+    }
+    let mut writer = serializer.for_writer(Vec::new());
+    for t in triples {
+        writer.serialize_triple(&t?)?;
+    }
+    Ok(writer.finish()?)
+}
+
+fn serialize_results(results: QueryResults, format: QueryResultsFormat) -> Result<Vec<u8>, Error> {
+    let serializer = QueryResultsSerializer::from_format(format);
+    match results {
+        QueryResults::Solutions(solutions) => {
+            let mut writer = serializer
+                .serialize_solutions_to_writer(Vec::new(), solutions.variables().to_vec())?;
+            for solution in solutions {
+                writer.serialize(&solution?)?;
+            }
+            Ok(writer.finish()?)
+        }
+        QueryResults::Boolean(value) => {
+            Ok(serializer.serialize_boolean_to_writer(Vec::new(), value)?)
+        }
+        QueryResults::Graph(_) => unreachable!("graph results are serialized as RDF"),
+    }
+}
+
+// Picks the supported media type the client prefers most, the first supported one for */*
+fn negotiate<F: Copy>(accept: &str, supported: &[(&'static str, F)]) -> Option<(&'static str, F)> {
+    let mut ranges: Vec<(&str, f32)> = accept
+        .split(',')
+        .map(|range| {
+            let mut parts = range.split(';').map(str::trim);
+            let media = parts.next().unwrap_or_default();
+            let q = parts
+                .find_map(|p| p.strip_prefix("q="))
+                .and_then(|q| q.parse().ok())
+                .unwrap_or(1.0);
+            (media, q)
+        })
+        .filter(|(_, q)| *q > 0.0)
+        .collect();
+    // stable, so ranges with the same weight keep the client's order
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranges.iter().find_map(|(media, _)| {
+        supported.iter().copied().find(|(supported, _)| {
+            *media == "*/*"
+                || media == supported
+                || media
+                    .strip_suffix("/*")
+                    .is_some_and(|kind| supported.starts_with(&format!("{kind}/")))
+        })
+    })
+}
+
+fn content(content_type: &str, body: Vec<u8>) -> Response<Full<Bytes>> {
+    Response::builder()
+        .header(CONTENT_TYPE, content_type)
+        .body(Full::new(Bytes::from(body)))
+        .expect("static headers are valid")
+}
+
+fn text(status: StatusCode, message: impl Into<String>) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(Full::new(Bytes::from(message.into())))
+        .expect("static headers are valid")
+}
+
+fn not_acceptable<F>(supported: &[(&str, F)]) -> Response<Full<Bytes>> {
+    let types: Vec<&str> = supported.iter().map(|(media, _)| *media).collect();
+    text(
+        StatusCode::NOT_ACCEPTABLE,
+        format!("Supported media types: {}", types.join(", ")),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    static DUMP: &str = r#"<https://pokeapi.co/api/v2/pokemon/25/> <https://schema.org/name> "pikachu" .
+<https://pokeapi.co/api/v2/pokemon/25/> <http://purl.org/pokemon/ontology#pokemonStat> _:s .
+_:s <http://purl.org/pokemon/ontology#baseStat> "90"^^<http://www.w3.org/2001/XMLSchema#integer> .
+<https://pokeapi.co/api/v2/pokemon/26/> <https://schema.org/name> "raichu" .
+"#;
+
+    async fn request(
+        method: Method,
+        uri: &str,
+        accept: &str,
+        body: &str,
+    ) -> (StatusCode, String, String) {
+        let store = Store::new().unwrap();
+        store
+            .load_from_reader(RdfFormat::NTriples, DUMP.as_bytes())
+            .unwrap();
+        let mut req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(ACCEPT, accept);
+        if !body.is_empty() {
+            req = req.header(CONTENT_TYPE, "application/sparql-query");
+        }
+        let response = respond(&store, &req.body(Bytes::from(body.to_string())).unwrap());
+        let content_type = response.headers()[CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (
+            status,
+            content_type,
+            String::from_utf8(body.to_vec()).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_sparql() {
+        let query = "/sparql?query=SELECT%20%3Fname%20WHERE%20%7B%20%3Fp%20%3Chttps%3A%2F%2Fschema.org%2Fname%3E%20%3Fname%20%7D%20ORDER%20BY%20%3Fname";
+        let (status, content_type, body) = request(Method::GET, query, "*/*", "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type, "application/sparql-results+json");
+        assert!(body.contains(r#""value":"pikachu""#));
+
+        let (_, content_type, body) = request(
+            Method::GET,
+            query,
+            "text/csv;q=0.9, application/sparql-results+xml;q=0.5",
+            "",
+        )
+        .await;
+        assert_eq!(content_type, "text/csv");
+        assert_eq!(body, "name\r\npikachu\r\nraichu\r\n");
+
+        let (status, _, _) = request(Method::GET, query, "image/png", "").await;
+        assert_eq!(status, StatusCode::NOT_ACCEPTABLE);
+
+        let (status, content_type, body) = request(
+            Method::POST,
+            "/sparql",
+            "application/n-triples",
+            "CONSTRUCT WHERE { ?p <https://schema.org/name> \"raichu\" }",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type, "application/n-triples");
+        assert_eq!(
+            body,
+            "<https://pokeapi.co/api/v2/pokemon/26/> <https://schema.org/name> \"raichu\" .\n"
+        );
+
+        let (status, _, _) = request(Method::GET, "/sparql?query=SELEKT", "*/*", "").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_dereference() {
+        let (status, content_type, body) =
+            request(Method::GET, "/pokemon/25", "text/turtle, */*;q=0.1", "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type, "text/turtle");
+        assert!(body.contains("schema:name \"pikachu\""));
+        // the stat blank node is part of the description
+        assert!(body.contains("poke:baseStat 90"));
+        assert!(!body.contains("raichu"));
+
+        let (status, _, _) = request(Method::GET, "/pokemon/99999", "*/*", "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
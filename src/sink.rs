@@ -0,0 +1,439 @@
+use oxrdf::vocab::xsd;
+use oxrdf::{Dataset, Graph, GraphNameRef, Quad, Term, Triple};
+use oxttl::turtle::LowLevelTurtleSerializer;
+use oxttl::TurtleSerializer;
+use std::collections::BTreeMap;
+use std::io::Write;
+use tokio::sync::mpsc;
+
+use crate::Error;
+use crate::PREFIXES;
+
+/// Where converters put the triples they build. Every resource starts with
+/// [`start_resource`](TripleSink::start_resource), followed by the triples built from it.
+///
+/// Sinks for [`Graph`], [`Dataset`] and channels are provided, as well as [`NTriplesSink`] and
+/// [`TurtleSink`] for files, [`Statistics`], [`Validate`] and [`Tee`] to combine them. Mutable
+/// references and boxes of sinks are sinks too, so a sink can be read after a build.
+pub trait TripleSink: Send {
+    /// The following triples were built from the PokeAPI resource at `url`.
+    fn start_resource(&mut self, url: &str) -> Result<(), Error> {
+        let _ = url;
+        Ok(())
+    }
+
+    /// Called with the sha256 of a resource's JSON, together with the JSON of the resources its
+    /// converter fetches along the way, before the resource is converted. A sink that
+    /// kept the triples of the same JSON from an earlier build hands them back, they are then
+    /// sent again instead of converting the resource.
+    fn reuse(&mut self, url: &str, json_hash: &str) -> Result<Option<Vec<Triple>>, Error> {
+        let _ = (url, json_hash);
+        Ok(None)
+    }
+
+    fn triple(&mut self, triple: Triple) -> Result<(), Error>;
+
+    /// A triple in a named graph. Sinks without named graphs keep the triple and drop the graph.
+    fn quad(&mut self, quad: Quad) -> Result<(), Error> {
+        self.triple(quad.into())
+    }
+
+    /// Called once after the last triple, e.g. to flush a file.
+    fn finish(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<S: TripleSink + ?Sized> TripleSink for &mut S {
+    fn start_resource(&mut self, url: &str) -> Result<(), Error> {
+        (**self).start_resource(url)
+    }
+
+    fn reuse(&mut self, url: &str, json_hash: &str) -> Result<Option<Vec<Triple>>, Error> {
+        (**self).reuse(url, json_hash)
+    }
+
+    fn triple(&mut self, triple: Triple) -> Result<(), Error> {
+        (**self).triple(triple)
+    }
+
+    fn quad(&mut self, quad: Quad) -> Result<(), Error> {
+        (**self).quad(quad)
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        (**self).finish()
+    }
+}
+
+impl<S: TripleSink + ?Sized> TripleSink for Box<S> {
+    fn start_resource(&mut self, url: &str) -> Result<(), Error> {
+        (**self).start_resource(url)
+    }
+
+    fn reuse(&mut self, url: &str, json_hash: &str) -> Result<Option<Vec<Triple>>, Error> {
+        (**self).reuse(url, json_hash)
+    }
+
+    fn triple(&mut self, triple: Triple) -> Result<(), Error> {
+        (**self).triple(triple)
+    }
+
+    fn quad(&mut self, quad: Quad) -> Result<(), Error> {
+        (**self).quad(quad)
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        (**self).finish()
+    }
+}
+
+impl TripleSink for Graph {
+    fn triple(&mut self, triple: Triple) -> Result<(), Error> {
+        self.insert(&triple);
+        Ok(())
+    }
+}
+
+/// Triples go to the default graph.
+impl TripleSink for Dataset {
+    fn triple(&mut self, triple: Triple) -> Result<(), Error> {
+        self.insert(triple.as_ref().in_graph(GraphNameRef::DefaultGraph));
+        Ok(())
+    }
+
+    fn quad(&mut self, quad: Quad) -> Result<(), Error> {
+        self.insert(&quad);
+        Ok(())
+    }
+}
+
+/// Fails with [`Error::ChannelClosed`] once the receiver is gone.
+impl TripleSink for mpsc::UnboundedSender<Triple> {
+    fn triple(&mut self, triple: Triple) -> Result<(), Error> {
+        Ok(self.send(triple)?)
+    }
+}
+
+/// Writes N-Triples.
+#[derive(Debug)]
+pub struct NTriplesSink<W: Write> {
+    writer: W,
+    comments: bool,
+}
+
+impl<W: Write> NTriplesSink<W> {
+    pub fn new(writer: W) -> Self {
+        NTriplesSink {
+            writer,
+            comments: false,
+        }
+    }
+
+    /// Heads every resource with a `# <url>` comment. N-Triples readers skip the comment,
+    /// incremental builds use it to find the resources of the previous dump.
+    pub fn with_comments(mut self) -> Self {
+        self.comments = true;
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write + Send> TripleSink for NTriplesSink<W> {
+    fn start_resource(&mut self, url: &str) -> Result<(), Error> {
+        if !self.comments {
+            return Ok(());
+        }
+        writeln!(self.writer, "# {url}").map_err(|e| Error::io("Error writing to output", e))
+    }
+
+    fn triple(&mut self, triple: Triple) -> Result<(), Error> {
+        writeln!(self.writer, "{triple} .").map_err(|e| Error::io("Error writing to output", e))
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        self.writer
+            .flush()
+            .map_err(|e| Error::io("Error flushing output", e))
+    }
+}
+
+/// Writes Turtle with the `poke`, `pokemonkg` and `schema` prefixes, grouping consecutive
+/// triples of a subject.
+pub struct TurtleSink<W: Write> {
+    serializer: LowLevelTurtleSerializer,
+    writer: W,
+}
+
+impl<W: Write> TurtleSink<W> {
+    pub fn new(writer: W) -> Result<Self, Error> {
+        let mut serializer = TurtleSerializer::new();
+        for (prefix, iri) in PREFIXES {
+            serializer = serializer.with_prefix(prefix, iri)?;
+        }
+        Ok(TurtleSink {
+            serializer: serializer.low_level(),
+            writer,
+        })
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write + Send> TripleSink for TurtleSink<W> {
+    fn triple(&mut self, triple: Triple) -> Result<(), Error> {
+        self.serializer
+            .serialize_triple(&triple, &mut self.writer)
+            .map_err(|e| Error::io("Error writing to output", e))
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        self.serializer
+            .finish(&mut self.writer)
+            .and_then(|_| self.writer.flush())
+            .map_err(|e| Error::io("Error flushing output", e))
+    }
+}
+
+/// Counts resources, triples and the uses of every predicate.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Statistics {
+    pub resources: usize,
+    pub triples: usize,
+    pub predicates: BTreeMap<String, usize>,
+}
+
+impl TripleSink for Statistics {
+    fn start_resource(&mut self, _url: &str) -> Result<(), Error> {
+        self.resources += 1;
+        Ok(())
+    }
+
+    fn triple(&mut self, triple: Triple) -> Result<(), Error> {
+        self.triples += 1;
+        *self
+            .predicates
+            .entry(triple.predicate.into_string())
+            .or_default() += 1;
+        Ok(())
+    }
+}
+
+/// Passes triples on to the inner sink after checking them, and fails with
+/// [`Error::InvalidData`] on the first triple that is outside a resource or has a numeric or
+/// boolean literal that does not parse.
+#[derive(Debug)]
+pub struct Validate<S> {
+    inner: S,
+    in_resource: bool,
+}
+
+impl<S: TripleSink> Validate<S> {
+    pub fn new(inner: S) -> Self {
+        Validate {
+            inner,
+            in_resource: false,
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn check(&self, triple: &Triple) -> Result<(), Error> {
+        if !self.in_resource {
+            return Err(Error::InvalidData(format!(
+                "{triple} is not part of a resource"
+            )));
+        }
+        if let Term::Literal(literal) = &triple.object {
+            let value = literal.value();
+            let datatype = literal.datatype();
+            let valid = if datatype == xsd::INTEGER {
+                value.parse::<i64>().is_ok()
+            } else if datatype == xsd::DECIMAL || datatype == xsd::DOUBLE {
+                value.parse::<f64>().is_ok()
+            } else if datatype == xsd::BOOLEAN {
+                matches!(value, "true" | "false" | "1" | "0")
+            } else {
+                true
+            };
+            if !valid {
+                return Err(Error::InvalidData(format!(
+                    "{triple} has an invalid {}",
+                    datatype.as_str()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S: TripleSink> TripleSink for Validate<S> {
+    fn start_resource(&mut self, url: &str) -> Result<(), Error> {
+        self.in_resource = true;
+        self.inner.start_resource(url)
+    }
+
+    fn reuse(&mut self, url: &str, json_hash: &str) -> Result<Option<Vec<Triple>>, Error> {
+        self.inner.reuse(url, json_hash)
+    }
+
+    fn triple(&mut self, triple: Triple) -> Result<(), Error> {
+        self.check(&triple)?;
+        self.inner.triple(triple)
+    }
+
+    fn quad(&mut self, quad: Quad) -> Result<(), Error> {
+        self.check(&quad.clone().into())?;
+        self.inner.quad(quad)
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        self.inner.finish()
+    }
+}
+
+/// Sends everything to both sinks, nest it for more.
+#[derive(Debug)]
+pub struct Tee<A, B>(pub A, pub B);
+
+impl<A: TripleSink, B: TripleSink> TripleSink for Tee<A, B> {
+    fn start_resource(&mut self, url: &str) -> Result<(), Error> {
+        self.0.start_resource(url)?;
+        self.1.start_resource(url)
+    }
+
+    // both sinks see the hash, the triples come from the first that kept them
+    fn reuse(&mut self, url: &str, json_hash: &str) -> Result<Option<Vec<Triple>>, Error> {
+        let first = self.0.reuse(url, json_hash)?;
+        let second = self.1.reuse(url, json_hash)?;
+        Ok(first.or(second))
+    }
+
+    fn triple(&mut self, triple: Triple) -> Result<(), Error> {
+        self.0.triple(triple.clone())?;
+        self.1.triple(triple)
+    }
+
+    fn quad(&mut self, quad: Quad) -> Result<(), Error> {
+        self.0.quad(quad.clone())?;
+        self.1.quad(quad)
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        self.0.finish()?;
+        self.1.finish()
+    }
+}
+
+/// Nothing is sent without a sink.
+impl<S: TripleSink> TripleSink for Option<S> {
+    fn start_resource(&mut self, url: &str) -> Result<(), Error> {
+        match self {
+            Some(sink) => sink.start_resource(url),
+            None => Ok(()),
+        }
+    }
+
+    fn reuse(&mut self, url: &str, json_hash: &str) -> Result<Option<Vec<Triple>>, Error> {
+        match self {
+            Some(sink) => sink.reuse(url, json_hash),
+            None => Ok(None),
+        }
+    }
+
+    fn triple(&mut self, triple: Triple) -> Result<(), Error> {
+        match self {
+            Some(sink) => sink.triple(triple),
+            None => Ok(()),
+        }
+    }
+
+    fn quad(&mut self, quad: Quad) -> Result<(), Error> {
+        match self {
+            Some(sink) => sink.quad(quad),
+            None => Ok(()),
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        match self {
+            Some(sink) => sink.finish(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use oxrdf::{Literal, NamedNode};
+
+    fn triple(object: impl Into<Term>) -> Triple {
+        Triple::new(
+            NamedNode::new("https://pokeapi.co/api/v2/pokemon/1/").unwrap(),
+            NamedNode::new("https://schema.org/identifier").unwrap(),
+            object,
+        )
+    }
+
+    #[test]
+    fn test_tee() {
+        let mut output = vec![];
+        let mut statistics = Statistics::default();
+        {
+            let mut sink = Tee(
+                NTriplesSink::new(&mut output).with_comments(),
+                &mut statistics,
+            );
+            sink.start_resource("https://pokeapi.co/api/v2/pokemon/1/")
+                .unwrap();
+            sink.triple(triple(Literal::new_typed_literal("1", xsd::INTEGER)))
+                .unwrap();
+            sink.finish().unwrap();
+        }
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "# https://pokeapi.co/api/v2/pokemon/1/\n<https://pokeapi.co/api/v2/pokemon/1/> <https://schema.org/identifier> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n"
+        );
+        assert_eq!(statistics.resources, 1);
+        assert_eq!(statistics.triples, 1);
+        assert_eq!(statistics.predicates["https://schema.org/identifier"], 1);
+    }
+
+    #[test]
+    fn test_turtle() {
+        let mut sink = TurtleSink::new(vec![]).unwrap();
+        sink.start_resource("https://pokeapi.co/api/v2/pokemon/1/")
+            .unwrap();
+        sink.triple(triple(Literal::new_typed_literal("1", xsd::INTEGER)))
+            .unwrap();
+        sink.finish().unwrap();
+        assert_eq!(
+            String::from_utf8(sink.into_inner()).unwrap(),
+            "@prefix poke: <http://purl.org/pokemon/ontology#> .\n@prefix pokemonkg: <https://pokemonkg.org/ontology#> .\n@prefix schema: <https://schema.org/> .\n<https://pokeapi.co/api/v2/pokemon/1/> schema:identifier 1 .\n"
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut sink = Validate::new(Graph::new());
+        assert!(sink.triple(triple(Literal::from(1))).is_err());
+        sink.start_resource("https://pokeapi.co/api/v2/pokemon/1/")
+            .unwrap();
+        sink.triple(triple(Literal::from(1))).unwrap();
+        sink.triple(triple(Literal::new_simple_literal("one")))
+            .unwrap();
+        assert!(sink
+            .triple(triple(Literal::new_typed_literal("one", xsd::INTEGER)))
+            .is_err());
+        assert_eq!(sink.into_inner().len(), 2);
+    }
+}
@@ -0,0 +1,459 @@
+use oxrdf::vocab::rdf;
+use oxrdf::{
+    Graph, NamedNode, NamedNodeRef, NamedOrBlankNode, NamedOrBlankNodeRef, Term, TermRef, Triple,
+};
+use oxttl::NTriplesParser;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufReader, BufWriter, Write};
+
+use crate::resource_id;
+use crate::Error;
+use crate::POKE;
+use crate::POKEAPI;
+use crate::POKEMONKG;
+
+// Damage relation predicates emitted directly on types by poke_types.rs
+static DAMAGE_RELATIONS: [&str; 6] = [
+    "doubleDamageFrom",
+    "doubleDamageTo",
+    "halfDamageFrom",
+    "halfDamageTo",
+    "noDamageFrom",
+    "noDamageTo",
+];
+
+/// What a snapshot is scoped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// Everything up to and including this generation, learnsets of all its version groups.
+    Generation(i64),
+    /// Everything up to and including the generation of this version group, learnsets of
+    /// this version group only.
+    VersionGroup(i64),
+}
+
+/// Reads a full N-Triples dump, projects it to `scope` and writes the result as N-Triples.
+pub fn snapshot_file(input: &str, output: &str, scope: Scope) -> Result<(), Error> {
+    let input_file = std::fs::File::open(input)
+        .map_err(|e| Error::io(format!("Failed to open input file {}", input), e))?;
+    let mut graph = Graph::new();
+    for triple in NTriplesParser::new().for_reader(BufReader::new(input_file)) {
+        graph.insert(&triple?);
+    }
+
+    let snapshot = snapshot(&graph, scope)?;
+
+    let output_file = std::fs::File::create(output)
+        .map_err(|e| Error::io(format!("Failed to create output file {}", output), e))?;
+    let mut writer = BufWriter::new(output_file);
+    for t in snapshot.iter() {
+        writeln!(writer, "{t} .")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Projects a full graph to what existed in the scoped generation: species, moves, abilities
+/// and types introduced later are dropped together with everything pointing at them,
+/// learnsets are limited to the scoped version groups and the type chart, type damage
+/// relations and Pokémon types are the ones that applied in that generation.
+pub fn snapshot(graph: &Graph, scope: Scope) -> Result<Graph, Error> {
+    let poke = |local: &str| NamedNode::new(format!("{POKE}{local}"));
+    let generation_predicate = poke("generation")?;
+
+    // generation of each version group, from the generation resources
+    let mut version_group_generations: HashMap<NamedNodeRef, i64> = HashMap::new();
+    for t in graph.triples_for_predicate(&poke("hasNewVersionGroup")?) {
+        if let (NamedOrBlankNodeRef::NamedNode(generation), TermRef::NamedNode(version_group)) =
+            (t.subject, t.object)
+        {
+            version_group_generations.insert(version_group, resource_id(generation.as_str())?);
+        }
+    }
+    let (generation, version_groups): (i64, HashSet<NamedNodeRef>) = match scope {
+        Scope::Generation(generation) => (
+            generation,
+            version_group_generations
+                .iter()
+                .filter(|(_, g)| **g == generation)
+                .map(|(vg, _)| *vg)
+                .collect(),
+        ),
+        Scope::VersionGroup(id) => {
+            let iri = NamedNode::new(format!("{POKEAPI}version-group/{id}/"))?;
+            let (version_group, generation) = version_group_generations
+                .iter()
+                .find(|(vg, _)| **vg == iri.as_ref())
+                .ok_or_else(|| {
+                    Error::InvalidData(format!("version group {} not found in the graph", iri))
+                })?;
+            (*generation, HashSet::from([*version_group]))
+        }
+    };
+
+    let mut excluded: HashSet<Term> = HashSet::new();
+
+    // species, moves, abilities and types introduced after the generation
+    let scoped_classes = [
+        NamedNode::new(format!("{POKE}PokemonSpecies"))?,
+        NamedNode::new(format!("{POKEMONKG}Move"))?,
+        NamedNode::new(format!("{POKEMONKG}Ability"))?,
+        NamedNode::new(format!("{POKE}PokemonType"))?,
+    ];
+    for t in graph.triples_for_predicate(&generation_predicate) {
+        let is_scoped = scoped_classes
+            .iter()
+            .any(|class| graph.contains(&Triple::new(t.subject, rdf::TYPE, class.clone())));
+        if is_scoped && generation_of(t.object)? > generation {
+            excluded.insert(t.subject.into_owned().into());
+        }
+    }
+    // Pokémon of excluded species, colours and other resources listing species only lose the link
+    let pokemon_class = poke("Pokemon")?;
+    for t in graph.triples_for_predicate(&poke("species")?) {
+        if excluded.contains(&t.object.into_owned())
+            && graph.contains(&Triple::new(t.subject, rdf::TYPE, pokemon_class.clone()))
+        {
+            excluded.insert(t.subject.into_owned().into());
+        }
+    }
+
+    // learnsets, drop version group details outside the scope and moves left without any
+    let version_group_predicate = poke("versionGroup")?;
+    let details_predicate = poke("versionGroupDetails")?;
+    for t in graph.triples_for_predicate(&details_predicate) {
+        let in_scope = match t.object {
+            TermRef::BlankNode(detail) => graph
+                .object_for_subject_predicate(detail, &version_group_predicate)
+                .is_some_and(|vg| match vg {
+                    TermRef::NamedNode(vg) => version_groups.contains(&vg),
+                    _ => false,
+                }),
+            _ => true,
+        };
+        if !in_scope {
+            excluded.insert(t.object.into_owned());
+        }
+    }
+    for t in graph.triples_for_predicate(&poke("pokemonMove")?) {
+        if let TermRef::BlankNode(pokemon_move) = t.object {
+            let learnable = graph
+                .objects_for_subject_predicate(pokemon_move, &details_predicate)
+                .any(|detail| !excluded.contains(&detail.into_owned()));
+            if !learnable {
+                excluded.insert(t.object.into_owned());
+            }
+        }
+    }
+
+    // type chart, only the effectiveness matrix of this generation
+    let type_effectiveness = poke("TypeEffectiveness")?;
+    for matchup in graph.subjects_for_predicate_object(rdf::TYPE, &type_effectiveness) {
+        let in_generation = match graph.object_for_subject_predicate(matchup, &generation_predicate)
+        {
+            Some(g) => generation_of(g)? == generation,
+            None => false,
+        };
+        if !in_generation {
+            excluded.insert(matchup.into_owned().into());
+        }
+    }
+
+    // past damage relations and past types replace the current ones where they applied
+    let mut projected: Vec<Triple> = vec![];
+    let mut replaced: HashSet<(NamedOrBlankNode, NamedNode)> = HashSet::new();
+    let relation_predicates = DAMAGE_RELATIONS
+        .iter()
+        .map(|local| poke(local))
+        .collect::<Result<Vec<_>, _>>()?;
+    let past_relations_predicate = poke("pastDamageRelations")?;
+    for t in graph.triples_for_predicate(&past_relations_predicate) {
+        excluded.insert(t.object.into_owned());
+    }
+    for subject in subjects_with(graph, &past_relations_predicate) {
+        let past = past_entries(graph, &subject, &past_relations_predicate, generation)?;
+        if let Some(past) = past.first() {
+            for predicate in &relation_predicates {
+                replaced.insert((subject.clone(), predicate.clone()));
+                for object in graph.objects_for_subject_predicate(past, predicate) {
+                    projected.push(Triple::new(
+                        subject.clone(),
+                        predicate.clone(),
+                        object.into_owned(),
+                    ));
+                }
+            }
+        }
+    }
+    let past_type_predicate = poke("pastType")?;
+    let has_type = NamedNode::new(format!("{POKEMONKG}hasType"))?;
+    for t in graph.triples_for_predicate(&past_type_predicate) {
+        excluded.insert(t.object.into_owned());
+    }
+    for subject in subjects_with(graph, &past_type_predicate) {
+        // one past type node per type, all nodes of a past entry share its generation
+        let past = past_entries(graph, &subject, &past_type_predicate, generation)?;
+        if !past.is_empty() {
+            replaced.insert((subject.clone(), has_type.clone()));
+            for node in past {
+                for object in graph.objects_for_subject_predicate(&node, &has_type) {
+                    projected.push(Triple::new(
+                        subject.clone(),
+                        has_type.clone(),
+                        object.into_owned(),
+                    ));
+                }
+            }
+        }
+    }
+
+    let mut kept = Graph::new();
+    for t in graph.iter() {
+        if excluded.contains(&t.subject.into_owned().into())
+            || excluded.contains(&t.object.into_owned())
+            || replaced.contains(&(t.subject.into_owned(), t.predicate.into_owned()))
+        {
+            continue;
+        }
+        kept.insert(t);
+    }
+    for t in &projected {
+        if !excluded.contains(&t.object) {
+            kept.insert(t);
+        }
+    }
+
+    Ok(without_orphaned_blank_nodes(graph, &kept))
+}
+
+// Generation number of a generation IRI
+fn generation_of(generation: TermRef) -> Result<i64, Error> {
+    match generation {
+        TermRef::NamedNode(g) => resource_id(g.as_str()),
+        other => Err(Error::InvalidData(format!("{} is not a generation", other))),
+    }
+}
+
+fn subjects_with(graph: &Graph, predicate: &NamedNode) -> HashSet<NamedOrBlankNode> {
+    graph
+        .triples_for_predicate(predicate)
+        .map(|t| t.subject.into_owned())
+        .collect()
+}
+
+// Past entries (reached through `predicate`) hold up to and including their generation, so
+// the ones in effect during `generation` share the lowest generation that is not before it.
+fn past_entries(
+    graph: &Graph,
+    subject: &NamedOrBlankNode,
+    predicate: &NamedNode,
+    generation: i64,
+) -> Result<Vec<NamedOrBlankNode>, Error> {
+    let generation_predicate = NamedNode::new(format!("{POKE}generation"))?;
+    let mut entries = vec![];
+    for node in graph.objects_for_subject_predicate(subject, predicate) {
+        let node: NamedOrBlankNode = match node {
+            TermRef::NamedNode(n) => n.into_owned().into(),
+            TermRef::BlankNode(n) => n.into_owned().into(),
+            _ => continue,
+        };
+        if let Some(g) = graph.object_for_subject_predicate(&node, &generation_predicate) {
+            let until = generation_of(g)?;
+            if until >= generation {
+                entries.push((until, node));
+            }
+        }
+    }
+    let Some(earliest) = entries.iter().map(|(until, _)| *until).min() else {
+        return Ok(vec![]);
+    };
+    Ok(entries
+        .into_iter()
+        .filter(|(until, _)| *until == earliest)
+        .map(|(_, node)| node)
+        .collect())
+}
+
+// Drops blank nodes that were referenced in the original graph but can no longer be reached
+// from a named resource or from a blank node nothing points at (like the matrix nodes)
+fn without_orphaned_blank_nodes(original: &Graph, graph: &Graph) -> Graph {
+    let mut reachable = HashSet::new();
+    let mut pending: Vec<_> = graph
+        .iter()
+        .filter(|t| match t.subject {
+            NamedOrBlankNodeRef::NamedNode(_) => true,
+            NamedOrBlankNodeRef::BlankNode(b) => original.triples_for_object(b).next().is_none(),
+        })
+        .flat_map(|t| {
+            let subject = match t.subject {
+                NamedOrBlankNodeRef::BlankNode(b) => Some(b),
+                NamedOrBlankNodeRef::NamedNode(_) => None,
+            };
+            let object = match t.object {
+                TermRef::BlankNode(b) => Some(b),
+                _ => None,
+            };
+            subject.into_iter().chain(object)
+        })
+        .collect();
+    while let Some(node) = pending.pop() {
+        if reachable.insert(node) {
+            pending.extend(
+                graph
+                    .triples_for_subject(node)
+                    .filter_map(|t| match t.object {
+                        TermRef::BlankNode(b) => Some(b),
+                        _ => None,
+                    }),
+            );
+        }
+    }
+
+    let mut connected = Graph::new();
+    for t in graph.iter() {
+        let keep = match t.subject {
+            NamedOrBlankNodeRef::NamedNode(_) => true,
+            NamedOrBlankNodeRef::BlankNode(b) => reachable.contains(&b),
+        };
+        if keep {
+            connected.insert(t);
+        }
+    }
+    connected
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn parse(nt: &str) -> Graph {
+        let mut graph = Graph::new();
+        for triple in NTriplesParser::new().for_slice(nt) {
+            graph.insert(&triple.unwrap());
+        }
+        graph
+    }
+
+    #[test]
+    fn test_snapshot_generation() {
+        let graph = parse(
+            r#"
+<https://pokeapi.co/api/v2/generation/1/> <http://purl.org/pokemon/ontology#hasNewVersionGroup> <https://pokeapi.co/api/v2/version-group/1/> .
+<https://pokeapi.co/api/v2/generation/2/> <http://purl.org/pokemon/ontology#hasNewVersionGroup> <https://pokeapi.co/api/v2/version-group/3/> .
+<https://pokeapi.co/api/v2/pokemon-species/35/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://purl.org/pokemon/ontology#PokemonSpecies> .
+<https://pokeapi.co/api/v2/pokemon-species/35/> <http://purl.org/pokemon/ontology#generation> <https://pokeapi.co/api/v2/generation/1/> .
+<https://pokeapi.co/api/v2/pokemon-species/173/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://purl.org/pokemon/ontology#PokemonSpecies> .
+<https://pokeapi.co/api/v2/pokemon-species/173/> <http://purl.org/pokemon/ontology#generation> <https://pokeapi.co/api/v2/generation/2/> .
+<https://pokeapi.co/api/v2/pokemon/173/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://purl.org/pokemon/ontology#Pokemon> .
+<https://pokeapi.co/api/v2/pokemon/173/> <http://purl.org/pokemon/ontology#species> <https://pokeapi.co/api/v2/pokemon-species/173/> .
+<https://pokeapi.co/api/v2/pokemon/173/> <https://schema.org/name> "cleffa" .
+<https://pokeapi.co/api/v2/pokemon-species/35/> <http://purl.org/pokemon/ontology#evolvesInto> <https://pokeapi.co/api/v2/pokemon-species/36/> .
+<https://pokeapi.co/api/v2/pokemon-species/173/> <http://purl.org/pokemon/ontology#evolvesInto> <https://pokeapi.co/api/v2/pokemon-species/35/> .
+<https://pokeapi.co/api/v2/type/18/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://purl.org/pokemon/ontology#PokemonType> .
+<https://pokeapi.co/api/v2/type/18/> <http://purl.org/pokemon/ontology#generation> <https://pokeapi.co/api/v2/generation/6/> .
+<https://pokeapi.co/api/v2/pokemon/35/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://purl.org/pokemon/ontology#Pokemon> .
+<https://pokeapi.co/api/v2/pokemon/35/> <http://purl.org/pokemon/ontology#species> <https://pokeapi.co/api/v2/pokemon-species/35/> .
+<https://pokeapi.co/api/v2/pokemon/35/> <https://pokemonkg.org/ontology#hasType> <https://pokeapi.co/api/v2/type/18/> .
+<https://pokeapi.co/api/v2/pokemon/35/> <http://purl.org/pokemon/ontology#pastType> _:pokemon35_pastType0_type0 .
+_:pokemon35_pastType0_type0 <https://pokemonkg.org/ontology#hasType> <https://pokeapi.co/api/v2/type/1/> .
+_:pokemon35_pastType0_type0 <http://purl.org/pokemon/ontology#generation> <https://pokeapi.co/api/v2/generation/5/> .
+<https://pokeapi.co/api/v2/pokemon/35/> <http://purl.org/pokemon/ontology#pokemonMove> _:pokemon35_move0 .
+_:pokemon35_move0 <http://purl.org/pokemon/ontology#move> <https://pokeapi.co/api/v2/move/1/> .
+_:pokemon35_move0 <http://purl.org/pokemon/ontology#versionGroupDetails> _:pokemon35_move0_versionGroup0 .
+_:pokemon35_move0_versionGroup0 <http://purl.org/pokemon/ontology#versionGroup> <https://pokeapi.co/api/v2/version-group/1/> .
+<https://pokeapi.co/api/v2/pokemon/35/> <http://purl.org/pokemon/ontology#pokemonMove> _:pokemon35_move1 .
+_:pokemon35_move1 <http://purl.org/pokemon/ontology#move> <https://pokeapi.co/api/v2/move/2/> .
+_:pokemon35_move1 <http://purl.org/pokemon/ontology#versionGroupDetails> _:pokemon35_move1_versionGroup0 .
+_:pokemon35_move1_versionGroup0 <http://purl.org/pokemon/ontology#versionGroup> <https://pokeapi.co/api/v2/version-group/3/> .
+"#,
+        );
+        let snapshot = snapshot(&graph, Scope::Generation(1)).unwrap();
+        let expected = parse(
+            r#"
+<https://pokeapi.co/api/v2/generation/1/> <http://purl.org/pokemon/ontology#hasNewVersionGroup> <https://pokeapi.co/api/v2/version-group/1/> .
+<https://pokeapi.co/api/v2/generation/2/> <http://purl.org/pokemon/ontology#hasNewVersionGroup> <https://pokeapi.co/api/v2/version-group/3/> .
+<https://pokeapi.co/api/v2/pokemon-species/35/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://purl.org/pokemon/ontology#PokemonSpecies> .
+<https://pokeapi.co/api/v2/pokemon-species/35/> <http://purl.org/pokemon/ontology#generation> <https://pokeapi.co/api/v2/generation/1/> .
+<https://pokeapi.co/api/v2/pokemon-species/35/> <http://purl.org/pokemon/ontology#evolvesInto> <https://pokeapi.co/api/v2/pokemon-species/36/> .
+<https://pokeapi.co/api/v2/pokemon/35/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://purl.org/pokemon/ontology#Pokemon> .
+<https://pokeapi.co/api/v2/pokemon/35/> <http://purl.org/pokemon/ontology#species> <https://pokeapi.co/api/v2/pokemon-species/35/> .
+<https://pokeapi.co/api/v2/pokemon/35/> <https://pokemonkg.org/ontology#hasType> <https://pokeapi.co/api/v2/type/1/> .
+<https://pokeapi.co/api/v2/pokemon/35/> <http://purl.org/pokemon/ontology#pokemonMove> _:pokemon35_move0 .
+_:pokemon35_move0 <http://purl.org/pokemon/ontology#move> <https://pokeapi.co/api/v2/move/1/> .
+_:pokemon35_move0 <http://purl.org/pokemon/ontology#versionGroupDetails> _:pokemon35_move0_versionGroup0 .
+_:pokemon35_move0_versionGroup0 <http://purl.org/pokemon/ontology#versionGroup> <https://pokeapi.co/api/v2/version-group/1/> .
+"#,
+        );
+        assert_eq!(snapshot, expected);
+    }
+    // A colour lists species of several generations, only the later ones are dropped from it
+    #[test]
+    fn test_snapshot_colour() {
+        let graph = parse(
+            r#"
+<https://pokeapi.co/api/v2/generation/1/> <http://purl.org/pokemon/ontology#hasNewVersionGroup> <https://pokeapi.co/api/v2/version-group/1/> .
+<https://pokeapi.co/api/v2/pokemon-species/1/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://purl.org/pokemon/ontology#PokemonSpecies> .
+<https://pokeapi.co/api/v2/pokemon-species/1/> <http://purl.org/pokemon/ontology#generation> <https://pokeapi.co/api/v2/generation/1/> .
+<https://pokeapi.co/api/v2/pokemon-species/1/> <https://pokemonkg.org/ontology#hasColour> <https://pokeapi.co/api/v2/pokemon-color/5/> .
+<https://pokeapi.co/api/v2/pokemon-species/152/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://purl.org/pokemon/ontology#PokemonSpecies> .
+<https://pokeapi.co/api/v2/pokemon-species/152/> <http://purl.org/pokemon/ontology#generation> <https://pokeapi.co/api/v2/generation/2/> .
+<https://pokeapi.co/api/v2/pokemon-species/152/> <https://pokemonkg.org/ontology#hasColour> <https://pokeapi.co/api/v2/pokemon-color/5/> .
+<https://pokeapi.co/api/v2/pokemon-species/252/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://purl.org/pokemon/ontology#PokemonSpecies> .
+<https://pokeapi.co/api/v2/pokemon-species/252/> <http://purl.org/pokemon/ontology#generation> <https://pokeapi.co/api/v2/generation/3/> .
+<https://pokeapi.co/api/v2/pokemon-color/5/> <https://schema.org/name> "green" .
+<https://pokeapi.co/api/v2/pokemon-color/5/> <http://purl.org/pokemon/ontology#species> <https://pokeapi.co/api/v2/pokemon-species/1/> .
+<https://pokeapi.co/api/v2/pokemon-color/5/> <http://purl.org/pokemon/ontology#species> <https://pokeapi.co/api/v2/pokemon-species/152/> .
+<https://pokeapi.co/api/v2/pokemon-color/5/> <http://purl.org/pokemon/ontology#species> <https://pokeapi.co/api/v2/pokemon-species/252/> .
+<https://pokeapi.co/api/v2/pokemon/1/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://purl.org/pokemon/ontology#Pokemon> .
+<https://pokeapi.co/api/v2/pokemon/1/> <http://purl.org/pokemon/ontology#species> <https://pokeapi.co/api/v2/pokemon-species/1/> .
+"#,
+        );
+        let snapshot = snapshot(&graph, Scope::Generation(1)).unwrap();
+        let expected = parse(
+            r#"
+<https://pokeapi.co/api/v2/generation/1/> <http://purl.org/pokemon/ontology#hasNewVersionGroup> <https://pokeapi.co/api/v2/version-group/1/> .
+<https://pokeapi.co/api/v2/pokemon-species/1/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://purl.org/pokemon/ontology#PokemonSpecies> .
+<https://pokeapi.co/api/v2/pokemon-species/1/> <http://purl.org/pokemon/ontology#generation> <https://pokeapi.co/api/v2/generation/1/> .
+<https://pokeapi.co/api/v2/pokemon-species/1/> <https://pokemonkg.org/ontology#hasColour> <https://pokeapi.co/api/v2/pokemon-color/5/> .
+<https://pokeapi.co/api/v2/pokemon-color/5/> <https://schema.org/name> "green" .
+<https://pokeapi.co/api/v2/pokemon-color/5/> <http://purl.org/pokemon/ontology#species> <https://pokeapi.co/api/v2/pokemon-species/1/> .
+<https://pokeapi.co/api/v2/pokemon/1/> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://purl.org/pokemon/ontology#Pokemon> .
+<https://pokeapi.co/api/v2/pokemon/1/> <http://purl.org/pokemon/ontology#species> <https://pokeapi.co/api/v2/pokemon-species/1/> .
+"#,
+        );
+        assert_eq!(snapshot, expected);
+    }
+
+    #[test]
+    fn test_snapshot_type_chart() {
+        let graph = parse(
+            r#"
+<https://pokeapi.co/api/v2/generation/1/> <http://purl.org/pokemon/ontology#hasNewVersionGroup> <https://pokeapi.co/api/v2/version-group/1/> .
+<https://pokeapi.co/api/v2/type/8/> <http://purl.org/pokemon/ontology#noDamageTo> <https://pokeapi.co/api/v2/type/1/> .
+<https://pokeapi.co/api/v2/type/8/> <http://purl.org/pokemon/ontology#doubleDamageTo> <https://pokeapi.co/api/v2/type/14/> .
+<https://pokeapi.co/api/v2/type/8/> <http://purl.org/pokemon/ontology#pastDamageRelations> _:type8_pastDamageRelations0 .
+_:type8_pastDamageRelations0 <http://purl.org/pokemon/ontology#generation> <https://pokeapi.co/api/v2/generation/1/> .
+_:type8_pastDamageRelations0 <http://purl.org/pokemon/ontology#noDamageTo> <https://pokeapi.co/api/v2/type/1/> .
+_:type8_pastDamageRelations0 <http://purl.org/pokemon/ontology#noDamageTo> <https://pokeapi.co/api/v2/type/14/> .
+_:type8_against_type14 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://purl.org/pokemon/ontology#TypeEffectiveness> .
+_:type8_against_type14 <http://purl.org/pokemon/ontology#damageMultiplier> "2"^^<http://www.w3.org/2001/XMLSchema#decimal> .
+_:type8_against_type14_generation1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://purl.org/pokemon/ontology#TypeEffectiveness> .
+_:type8_against_type14_generation1 <http://purl.org/pokemon/ontology#damageMultiplier> "0"^^<http://www.w3.org/2001/XMLSchema#decimal> .
+_:type8_against_type14_generation1 <http://purl.org/pokemon/ontology#generation> <https://pokeapi.co/api/v2/generation/1/> .
+"#,
+        );
+        let snapshot = snapshot(&graph, Scope::VersionGroup(1)).unwrap();
+        let expected = parse(
+            r#"
+<https://pokeapi.co/api/v2/generation/1/> <http://purl.org/pokemon/ontology#hasNewVersionGroup> <https://pokeapi.co/api/v2/version-group/1/> .
+<https://pokeapi.co/api/v2/type/8/> <http://purl.org/pokemon/ontology#noDamageTo> <https://pokeapi.co/api/v2/type/1/> .
+<https://pokeapi.co/api/v2/type/8/> <http://purl.org/pokemon/ontology#noDamageTo> <https://pokeapi.co/api/v2/type/14/> .
+_:type8_against_type14_generation1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://purl.org/pokemon/ontology#TypeEffectiveness> .
+_:type8_against_type14_generation1 <http://purl.org/pokemon/ontology#damageMultiplier> "0"^^<http://www.w3.org/2001/XMLSchema#decimal> .
+_:type8_against_type14_generation1 <http://purl.org/pokemon/ontology#generation> <https://pokeapi.co/api/v2/generation/1/> .
+"#,
+        );
+        assert_eq!(snapshot, expected);
+    }
+}
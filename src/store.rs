@@ -0,0 +1,311 @@
+use oxigraph::io::RdfFormat;
+use oxigraph::model::TermRef;
+use oxigraph::sparql::results::{QueryResultsFormat, QueryResultsSerializer};
+use oxigraph::sparql::{QueryResults, SparqlEvaluator};
+use oxigraph::store::Store;
+use std::io::{BufReader, Write};
+use std::path::Path;
+
+use crate::Error;
+
+/// How `SELECT` and `ASK` results are printed. `CONSTRUCT` and `DESCRIBE` results are always
+/// written as N-Triples.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResultFormat {
+    /// Aligned columns with IRIs and literal values, for reading in a terminal.
+    #[default]
+    Table,
+    /// SPARQL 1.1 Query Results CSV.
+    Csv,
+    /// SPARQL 1.1 Query Results JSON.
+    Json,
+}
+
+/// Fails unless this build can write and open on-disk stores. They are RocksDB databases, which
+/// are only built with the `rocksdb` feature.
+pub fn check_store_support() -> Result<(), Error> {
+    if cfg!(feature = "rocksdb") {
+        Ok(())
+    } else {
+        Err(no_rocksdb())
+    }
+}
+
+fn no_rocksdb() -> Error {
+    Error::InvalidData(
+        "on-disk stores need pokemon2rdf built with `--features rocksdb`".to_string(),
+    )
+}
+
+/// Replaces the content of the on-disk store at `path` with an N-Triples dump. The store is
+/// created if it does not exist yet.
+#[cfg(feature = "rocksdb")]
+pub fn load_store(path: &Path, dump: &Path) -> Result<(), Error> {
+    let file = std::fs::File::open(dump)
+        .map_err(|e| Error::io(format!("Failed to open {}", dump.display()), e))?;
+    let store = Store::open(path)?;
+    store.clear()?;
+    let mut loader = store.bulk_loader();
+    loader.load_from_reader(RdfFormat::NTriples, BufReader::new(file))?;
+    loader.commit()?;
+    Ok(())
+}
+
+/// Opens a store written by `build --store` for querying, without loading it into memory.
+#[cfg(feature = "rocksdb")]
+pub fn open_store(path: &Path) -> Result<Store, Error> {
+    if !path.exists() {
+        return Err(Error::InvalidData(format!(
+            "No store at {}, create it with `build --store`",
+            path.display()
+        )));
+    }
+    Ok(Store::open_read_only(path)?)
+}
+
+/// Without the `rocksdb` feature there is no on-disk store to write to.
+#[cfg(not(feature = "rocksdb"))]
+pub fn load_store(_path: &Path, _dump: &Path) -> Result<(), Error> {
+    Err(no_rocksdb())
+}
+
+/// Without the `rocksdb` feature there is no on-disk store to open.
+#[cfg(not(feature = "rocksdb"))]
+pub fn open_store(_path: &Path) -> Result<Store, Error> {
+    Err(no_rocksdb())
+}
+
+/// Loads a dump into an in-memory store, to query a file without building a store first.
+pub fn memory_store(dump: &Path) -> Result<Store, Error> {
+    let file = std::fs::File::open(dump)
+        .map_err(|e| Error::io(format!("Failed to open {}", dump.display()), e))?;
+    let store = Store::new()?;
+    let mut loader = store.bulk_loader();
+    loader.load_from_reader(RdfFormat::NTriples, BufReader::new(file))?;
+    loader.commit()?;
+    Ok(store)
+}
+
+/// Runs a SPARQL query against `store` and writes the results to `writer`.
+pub fn query(
+    store: &Store,
+    query: &str,
+    format: ResultFormat,
+    mut writer: impl Write,
+) -> Result<(), Error> {
+    let results = SparqlEvaluator::new()
+        .parse_query(query)?
+        .on_store(store)
+        .execute()?;
+    match (results, format) {
+        (QueryResults::Solutions(solutions), ResultFormat::Table) => {
+            let variables = solutions.variables().to_vec();
+            let mut rows = vec![variables.iter().map(|v| v.as_str().to_string()).collect()];
+            for solution in solutions {
+                let solution = solution?;
+                rows.push(
+                    variables
+                        .iter()
+                        .map(|v| {
+                            solution
+                                .get(v)
+                                .map(|t| cell(t.as_ref()))
+                                .unwrap_or_default()
+                        })
+                        .collect(),
+                );
+            }
+            write_table(&rows, &mut writer)?;
+        }
+        (QueryResults::Solutions(solutions), format) => {
+            let mut serializer = QueryResultsSerializer::from_format(results_format(format))
+                .serialize_solutions_to_writer(&mut writer, solutions.variables().to_vec())?;
+            for solution in solutions {
+                serializer.serialize(&solution?)?;
+            }
+            serializer.finish()?;
+        }
+        (QueryResults::Boolean(value), ResultFormat::Table) => writeln!(writer, "{value}")?,
+        (QueryResults::Boolean(value), format) => {
+            QueryResultsSerializer::from_format(results_format(format))
+                .serialize_boolean_to_writer(&mut writer, value)?;
+        }
+        (QueryResults::Graph(triples), _) => {
+            for t in triples {
+                writeln!(writer, "{} .", t?)?;
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn results_format(format: ResultFormat) -> QueryResultsFormat {
+    match format {
+        ResultFormat::Csv => QueryResultsFormat::Csv,
+        _ => QueryResultsFormat::Json,
+    }
+}
+
+// IRIs and literal values without N-Triples syntax
+fn cell(term: TermRef) -> String {
+    match term {
+        TermRef::NamedNode(n) => n.as_str().to_string(),
+        TermRef::Literal(l) => l.value().to_string(),
+        term => term.to_string(),
+    }
+}
+
+fn write_table(rows: &[Vec<String>], writer: &mut impl Write) -> Result<(), Error> {
+    let mut widths = vec![0; rows.first().map_or(0, Vec::len)];
+    for row in rows {
+        for (width, value) in widths.iter_mut().zip(row) {
+            *width = (*width).max(value.chars().count());
+        }
+    }
+    for (i, row) in rows.iter().enumerate() {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!("{value:width$}"))
+            .collect();
+        writeln!(writer, "{}", line.join(" | ").trim_end())?;
+        if i == 0 {
+            let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+            writeln!(writer, "{}", rule.join("-+-"))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::collections::fixtures;
+    use crate::mock::Mock;
+    use crate::{Builder, ClientConfig};
+
+    static DUMP: &str = r#"<https://pokeapi.co/api/v2/pokemon/1/> <https://schema.org/name> "bulbasaur" .
+<https://pokeapi.co/api/v2/pokemon/1/> <http://purl.org/pokemon/ontology#pokemonStat> _:s1 .
+_:s1 <http://purl.org/pokemon/ontology#stat> <https://pokeapi.co/api/v2/stat/4/> .
+_:s1 <http://purl.org/pokemon/ontology#baseStat> "65"^^<http://www.w3.org/2001/XMLSchema#integer> .
+<https://pokeapi.co/api/v2/pokemon/2/> <https://schema.org/name> "ivysaur" .
+<https://pokeapi.co/api/v2/pokemon/2/> <http://purl.org/pokemon/ontology#pokemonStat> _:s2 .
+_:s2 <http://purl.org/pokemon/ontology#stat> <https://pokeapi.co/api/v2/stat/4/> .
+_:s2 <http://purl.org/pokemon/ontology#baseStat> "80"^^<http://www.w3.org/2001/XMLSchema#integer> .
+"#;
+
+    static QUERY: &str = r#"PREFIX schema: <https://schema.org/>
+PREFIX poke: <http://purl.org/pokemon/ontology#>
+SELECT ?name ?specialAttack WHERE {
+  ?pokemon schema:name ?name ; poke:pokemonStat ?stat .
+  ?stat poke:stat <https://pokeapi.co/api/v2/stat/4/> ; poke:baseStat ?specialAttack .
+}
+ORDER BY DESC(?specialAttack)"#;
+
+    fn run(format: ResultFormat) -> String {
+        let store = Store::new().unwrap();
+        store
+            .load_from_reader(RdfFormat::NTriples, DUMP.as_bytes())
+            .unwrap();
+        let mut output = vec![];
+        query(&store, QUERY, format, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_query() {
+        assert_eq!(
+            run(ResultFormat::Table),
+            "name      | specialAttack\n----------+--------------\nivysaur   | 80\nbulbasaur | 65\n"
+        );
+        assert_eq!(
+            run(ResultFormat::Csv),
+            "name,specialAttack\r\nivysaur,80\r\nbulbasaur,65\r\n"
+        );
+        assert!(run(ResultFormat::Json).starts_with(r#"{"head":{"vars":["name","specialAttack"]}"#));
+    }
+
+    // Every examples/*.rq against the graph of the fixture corpus, compared with the CSV results
+    // next to it
+    #[tokio::test]
+    async fn test_examples() {
+        let server = Mock::new(fixtures::corpus()).start().await;
+        let (graph, failures) = Builder::new()
+            .client(ClientConfig {
+                requests_per_second: 0.0,
+                base_url: server.base_url(),
+                ..ClientConfig::default()
+            })
+            .graph()
+            .await
+            .unwrap();
+        assert_eq!(failures, vec![]);
+        let nt: String = graph.iter().map(|t| format!("{t} .\n")).collect();
+        let store = Store::new().unwrap();
+        store
+            .load_from_reader(RdfFormat::NTriples, nt.as_bytes())
+            .unwrap();
+
+        let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+        let mut queries: Vec<_> = std::fs::read_dir(&examples)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|e| e == "rq"))
+            .collect();
+        queries.sort();
+        assert!(!queries.is_empty());
+        let mut failed = vec![];
+        for path in queries {
+            let mut output = vec![];
+            query(
+                &store,
+                &std::fs::read_to_string(&path).unwrap(),
+                ResultFormat::Csv,
+                &mut output,
+            )
+            .unwrap();
+            let actual = String::from_utf8(output).unwrap();
+            if std::env::var_os("UPDATE_GOLDEN").is_some() {
+                std::fs::write(path.with_extension("csv"), &actual).unwrap();
+            }
+            let expected = std::fs::read_to_string(path.with_extension("csv"))
+                .unwrap_or_else(|_| panic!("{} has no expected results", path.display()));
+            if actual != expected {
+                failed.push(format!(
+                    "{}\nexpected:\n{}\nactual:\n{}",
+                    path.display(),
+                    expected,
+                    actual
+                ));
+            }
+        }
+        assert!(failed.is_empty(), "{}", failed.join("\n"));
+    }
+
+    // The queries shown in the README are the tested ones in examples/, verbatim
+    #[test]
+    fn test_readme_queries() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let readme = std::fs::read_to_string(root.join("README.md")).unwrap();
+        let examples: Vec<String> = std::fs::read_dir(root.join("examples"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|e| e == "rq"))
+            .map(|path| std::fs::read_to_string(path).unwrap())
+            .collect();
+        let blocks: Vec<&str> = readme
+            .split("```sparql\n")
+            .skip(1)
+            .map(|block| block.split("\n```").next().unwrap())
+            .collect();
+        assert!(!blocks.is_empty());
+        for block in blocks {
+            assert!(
+                examples.iter().any(|query| query.trim_end() == block),
+                "README query is not in examples/:\n{block}"
+            );
+        }
+    }
+}